@@ -0,0 +1,117 @@
+//! A sample plugin with alternative solutions for days 1 and 6.
+//!
+//! ```sh
+//! cargo build --example plugin
+//! cargo run -- 6 2 --plugin target/debug/examples/libplugin.so
+//! ```
+
+use std::collections::HashSet;
+use std::ffi::{c_char, c_int, CString};
+use std::panic;
+
+use aoc2022::plugin::{PluginDescriptor, PluginPart, ABI_VERSION};
+
+const OK: c_int = 0;
+const ERROR: c_int = 1;
+const PANICKED: c_int = 2;
+
+static PARTS: [PluginPart; 4] = [
+    part(2022, 1, 1),
+    part(2022, 1, 2),
+    part(2022, 6, 1),
+    part(2022, 6, 2),
+];
+
+const fn part(year: u16, day: u8, part: u8) -> PluginPart {
+    PluginPart { year, day, part }
+}
+
+static DESCRIPTOR: PluginDescriptor = PluginDescriptor {
+    abi_version: ABI_VERSION,
+    name: c"sample-plugin".as_ptr(),
+    parts: PARTS.as_ptr(),
+    parts_len: PARTS.len(),
+    solve: Some(solve),
+    free_string: Some(free_string),
+};
+
+#[no_mangle]
+pub extern "C" fn aoc_plugin_entry() -> *const PluginDescriptor {
+    &DESCRIPTOR
+}
+
+/// # Safety
+///
+/// `input` must point to `input_len` readable bytes and `answer` must be
+/// writable.
+unsafe extern "C" fn solve(
+    year: u16,
+    day: u8,
+    part: u8,
+    input: *const u8,
+    input_len: usize,
+    answer: *mut *mut c_char,
+) -> c_int {
+    let input = std::slice::from_raw_parts(input, input_len);
+
+    // Unwinding across the C boundary is undefined behaviour
+    let result = panic::catch_unwind(|| {
+        let input = std::str::from_utf8(input).map_err(|e| e.to_string())?;
+        match (year, day, part) {
+            (2022, 1, 1) => calories(input, 1),
+            (2022, 1, 2) => calories(input, 3),
+            (2022, 6, 1) => marker(input, 4),
+            (2022, 6, 2) => marker(input, 14),
+            _ => Err(format!(
+                "{} day {} part {} is not solved here",
+                year, day, part
+            )),
+        }
+    });
+
+    let (code, message) = match result {
+        Ok(Ok(value)) => (OK, value),
+        Ok(Err(message)) => (ERROR, message),
+        Err(_) => (PANICKED, "The solver panicked".to_string()),
+    };
+
+    *answer = CString::new(message)
+        .unwrap_or_else(|_| c"Message contained a nul byte".to_owned())
+        .into_raw();
+    code
+}
+
+/// # Safety
+///
+/// `s` must have been returned from `solve` and not freed yet.
+unsafe extern "C" fn free_string(s: *mut c_char) {
+    if !s.is_null() {
+        drop(CString::from_raw(s));
+    }
+}
+
+/// Total calories carried by the `top` elves carrying the most
+fn calories(input: &str, top: usize) -> Result<String, String> {
+    let mut elves = input
+        .split("\n\n")
+        .map(|elf| {
+            elf.lines()
+                .map(|l| l.parse::<u32>().map_err(|e| format!("{}: {}", l, e)))
+                .sum::<Result<u32, String>>()
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    elves.sort_unstable_by(|a, b| b.cmp(a));
+    Ok(elves.iter().take(top).sum::<u32>().to_string())
+}
+
+/// Position just after the first `size` distinct characters
+fn marker(input: &str, size: usize) -> Result<String, String> {
+    input
+        .trim()
+        .as_bytes()
+        .windows(size)
+        .position(|w| w.iter().collect::<HashSet<_>>().len() == size)
+        .map(|idx| (idx + size).to_string())
+        .ok_or_else(|| "No marker found".to_string())
+}
@@ -0,0 +1,59 @@
+/*
+ * C API for the Advent of Code 2022 solutions.
+ *
+ * Ownership: the input buffer passed to aoc_solve belongs to the caller and is
+ * only read during the call. Every string aoc_solve returns through `answer`
+ * belongs to the caller and must be released with aoc_string_free, not free.
+ */
+
+#ifndef AOC2022_H
+#define AOC2022_H
+
+/* Generated by cbindgen from ffi/src/lib.rs, do not edit by hand. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// The outcome of a call to `aoc_solve`
+typedef enum AocStatus {
+  // `*answer` holds the answer
+  AOC_STATUS_OK = 0,
+  // There is no solver for the day and part
+  AOC_STATUS_UNKNOWN_PART = 1,
+  // The input pointer was null or the input is not valid UTF-8
+  AOC_STATUS_INVALID_INPUT = 2,
+  // The solver returned an error, `*answer` holds the message
+  AOC_STATUS_SOLVER_FAILED = 3,
+  // The solver panicked, `*answer` holds the panic message
+  AOC_STATUS_PANICKED = 4,
+} AocStatus;
+
+// Solves a part of a 2022 day for the given input.
+//
+// On success `*answer` is set to the answer. On failure it is set to a
+// message describing the error. Either way the string must be released
+// with `aoc_string_free`. `answer` may be null if the caller does not want
+// the string. The solvers print their progress as they run, so the process's
+// stdout is sent to `/dev/null` for the duration of the call.
+//
+// # Safety
+//
+// `input` must point to `input_len` readable bytes (it may be null when
+// `input_len` is 0) and `answer` must be null or writable.
+enum AocStatus aoc_solve(uint8_t day,
+                         uint8_t part,
+                         const uint8_t *input,
+                         size_t input_len,
+                         char **answer);
+
+// Releases a string returned by `aoc_solve`. Passing null does nothing.
+//
+// # Safety
+//
+// `s` must be null or a string from `aoc_solve` that has not been freed yet.
+void aoc_string_free(char *s);
+
+#endif /* AOC2022_H */
@@ -0,0 +1,194 @@
+//! A C API for the solvers, so they can be called from C and C++ test rigs.
+//!
+//! `include/aoc2022.h` is generated from this file with cbindgen. The
+//! `test_header` test fails when it is out of date; run the tests with
+//! `UPDATE_SNAPSHOTS=1` to regenerate it.
+//!
+//! Ownership: the input buffer belongs to the caller and is only read during
+//! the call. Every string returned through `aoc_solve` is owned by the caller
+//! and must be released with `aoc_string_free`, never with `free`.
+
+use std::ffi::{c_char, CString};
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+
+use aoc2022::registry::Registry;
+use aoc2022::utils::SilencedStdout;
+use aoc2022::YEAR;
+
+/// The outcome of a call to `aoc_solve`
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AocStatus {
+    /// `*answer` holds the answer
+    Ok = 0,
+    /// There is no solver for the day and part
+    UnknownPart = 1,
+    /// The input pointer was null or the input is not valid UTF-8
+    InvalidInput = 2,
+    /// The solver returned an error, `*answer` holds the message
+    SolverFailed = 3,
+    /// The solver panicked, `*answer` holds the panic message
+    Panicked = 4,
+}
+
+/// Solves a part of a 2022 day for the given input.
+///
+/// On success `*answer` is set to the answer. On failure it is set to a
+/// message describing the error. Either way the string must be released
+/// with `aoc_string_free`. `answer` may be null if the caller does not want
+/// the string. The solvers print their progress as they run, so the process's
+/// stdout is sent to `/dev/null` for the duration of the call.
+///
+/// # Safety
+///
+/// `input` must point to `input_len` readable bytes (it may be null when
+/// `input_len` is 0) and `answer` must be null or writable.
+#[no_mangle]
+pub unsafe extern "C" fn aoc_solve(
+    day: u8,
+    part: u8,
+    input: *const u8,
+    input_len: usize,
+    answer: *mut *mut c_char,
+) -> AocStatus {
+    if !answer.is_null() {
+        *answer = ptr::null_mut();
+    }
+
+    let input = if input_len == 0 {
+        &[]
+    } else if input.is_null() {
+        return respond(AocStatus::InvalidInput, "input is null", answer);
+    } else {
+        std::slice::from_raw_parts(input, input_len)
+    };
+
+    let Ok(input) = std::str::from_utf8(input) else {
+        return respond(AocStatus::InvalidInput, "input is not valid UTF-8", answer);
+    };
+
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        // Restored when dropped, including when the solver panics
+        let _silenced = SilencedStdout::new().ok();
+        let registry = Registry::with_built_ins();
+        registry
+            .solvers(YEAR, day, part)
+            .first()
+            .map(|solver| solver.solve(YEAR, day, part, input))
+    }));
+
+    match result {
+        Ok(Some(Ok(value))) => respond(AocStatus::Ok, &value, answer),
+        Ok(Some(Err(err))) => respond(AocStatus::SolverFailed, &format!("{:#}", err), answer),
+        Ok(None) => respond(
+            AocStatus::UnknownPart,
+            &format!("Nothing is registered for day {} part {}", day, part),
+            answer,
+        ),
+        Err(panic) => {
+            let message = panic
+                .downcast_ref::<&str>()
+                .map(|s| s.to_string())
+                .or_else(|| panic.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "the solver panicked".to_string());
+            respond(AocStatus::Panicked, &message, answer)
+        }
+    }
+}
+
+/// Releases a string returned by `aoc_solve`. Passing null does nothing.
+///
+/// # Safety
+///
+/// `s` must be null or a string from `aoc_solve` that has not been freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn aoc_string_free(s: *mut c_char) {
+    if !s.is_null() {
+        drop(CString::from_raw(s));
+    }
+}
+
+/// Hands `message` to the caller through `answer`
+unsafe fn respond(status: AocStatus, message: &str, answer: *mut *mut c_char) -> AocStatus {
+    if !answer.is_null() {
+        // Answers never contain nul bytes, but an error message could
+        let message = CString::new(message.replace('\0', "")).unwrap_or_default();
+        *answer = message.into_raw();
+    }
+    status
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::CStr;
+    use std::fs;
+    use std::path::Path;
+
+    unsafe fn call(day: u8, part: u8, input: &str) -> (AocStatus, String) {
+        let mut out = ptr::null_mut();
+        let status = aoc_solve(day, part, input.as_ptr(), input.len(), &mut out);
+        let message = CStr::from_ptr(out).to_string_lossy().into_owned();
+        aoc_string_free(out);
+        (status, message)
+    }
+
+    #[test]
+    fn test_solve() {
+        let input = "2-4,6-8\n2-3,4-5\n5-7,7-9\n2-8,3-7\n6-6,4-6\n2-6,4-8";
+
+        assert_eq!((AocStatus::Ok, "2".to_string()), unsafe {
+            call(4, 1, input)
+        });
+        assert_eq!((AocStatus::Ok, "4".to_string()), unsafe {
+            call(4, 2, input)
+        });
+    }
+
+    #[test]
+    fn test_errors() {
+        let (status, message) = unsafe { call(16, 1, "") };
+        assert_eq!(AocStatus::UnknownPart, status);
+        assert_eq!("Nothing is registered for day 16 part 1", message);
+
+        let (status, message) = unsafe { call(6, 1, "aaaaaa") };
+        assert_eq!(AocStatus::SolverFailed, status);
+        assert_eq!("Did not find the start of packet", message);
+
+        let (status, message) = unsafe { call(4, 1, "2-4,6-x") };
+        assert_eq!(AocStatus::SolverFailed, status);
+        assert_eq!("Invalid line 0: invalid digit found in string", message);
+
+        let invalid = [0xff, 0xfe];
+        let status = unsafe { aoc_solve(6, 1, invalid.as_ptr(), 2, ptr::null_mut()) };
+        assert_eq!(AocStatus::InvalidInput, status);
+
+        let status = unsafe { aoc_solve(6, 1, ptr::null(), 4, ptr::null_mut()) };
+        assert_eq!(AocStatus::InvalidInput, status);
+    }
+
+    #[test]
+    fn test_header() {
+        let crate_dir = env!("CARGO_MANIFEST_DIR");
+        let path = Path::new(crate_dir).join("include").join("aoc2022.h");
+
+        let mut header = Vec::new();
+        cbindgen::generate(crate_dir)
+            .expect("Could not generate the header")
+            .write(&mut header);
+        let header = String::from_utf8(header).unwrap();
+
+        if std::env::var("UPDATE_SNAPSHOTS").is_ok_and(|v| v == "1") {
+            fs::write(&path, header).unwrap();
+            return;
+        }
+
+        let expected = fs::read_to_string(&path).unwrap_or_default();
+        assert!(
+            expected == header,
+            "{} is out of date. Run the tests with UPDATE_SNAPSHOTS=1 to regenerate it.",
+            path.display()
+        );
+    }
+}
@@ -0,0 +1,11 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(input) = std::str::from_utf8(data) {
+        let hm = aoc2022::day12::HeightMap::parse(input);
+        let _ = hm.build_graph(|current, next| (next - current) <= 1);
+        let _ = hm.build_graph(|current, next| (current - next) <= 1);
+    }
+});
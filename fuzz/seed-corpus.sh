@@ -0,0 +1,11 @@
+#!/bin/sh
+# Seeds each fuzz target's corpus with the puzzle input from resources/
+set -e
+
+cd "$(dirname "$0")"
+
+for target in day01 day02 day03 day04 day05 day06 day07 day08 day09 day10 day11 day12 day13 day14 day15; do
+    day=$(echo "$target" | sed 's/^day0*//')
+    mkdir -p "corpus/$target"
+    cp "../resources/day$day.txt" "corpus/$target/"
+done
@@ -0,0 +1,58 @@
+/*
+ * The ABI for solver plugins, the C side of src/plugin.rs.
+ *
+ * A plugin is a shared library exporting aoc_plugin_entry, which returns a
+ * pointer to a static AocPluginDescriptor. The harness checks abi_version
+ * before using anything else in the descriptor, and refuses a descriptor
+ * whose solve or free_string is null.
+ *
+ * Ownership: the input buffer passed to solve belongs to the harness and is
+ * only valid during the call. The string solve stores in *answer belongs to
+ * the plugin and is handed back to its free_string once it has been copied.
+ */
+
+#ifndef AOC_PLUGIN_H
+#define AOC_PLUGIN_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#define AOC_PLUGIN_ABI_VERSION 2
+
+/* A part of a puzzle solved by the plugin */
+typedef struct AocPluginPart {
+  uint16_t year;
+  uint8_t day;
+  uint8_t part;
+} AocPluginPart;
+
+/*
+ * Returns 0 and stores the answer in *answer, or returns any other value and
+ * stores an error message there. Either string must be nul terminated.
+ */
+typedef int (*AocSolveFn)(uint16_t year,
+                          uint8_t day,
+                          uint8_t part,
+                          const uint8_t *input,
+                          size_t input_len,
+                          char **answer);
+
+/* Releases a string stored by AocSolveFn */
+typedef void (*AocFreeStringFn)(char *s);
+
+typedef struct AocPluginDescriptor {
+  /* Must be AOC_PLUGIN_ABI_VERSION */
+  uint32_t abi_version;
+  /* A nul terminated name to report the answers under */
+  const char *name;
+  const AocPluginPart *parts;
+  size_t parts_len;
+  /* Neither may be null */
+  AocSolveFn solve;
+  AocFreeStringFn free_string;
+} AocPluginDescriptor;
+
+/* The symbol every plugin exports */
+const AocPluginDescriptor *aoc_plugin_entry(void);
+
+#endif /* AOC_PLUGIN_H */
@@ -0,0 +1,158 @@
+//! An on-disk cache of answers, so heavy parts are not recomputed when
+//! neither the solver nor the input changed.
+//!
+//! Each answer is stored in its own file, named after everything it depends
+//! on: the year, day and part, the solver that computed it, the solver's
+//! version and a hash of the input. Answers can span several lines (the CRT in
+//! day 10), so the file holds the answer as is.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+
+/// Where the cache lives, next to `resources/`
+pub const CACHE_DIR: &str = "./.aoc-cache";
+
+/// Everything a cached answer depends on
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CacheKey {
+    pub year: u16,
+    pub day: u8,
+    pub part: u8,
+
+    /// The solver that computed the answer
+    pub source: String,
+    pub version: String,
+
+    pub input_hash: u64,
+}
+
+impl CacheKey {
+    /// The start of the file name shared by every version of this solver
+    fn prefix(&self) -> String {
+        format!(
+            "day{}-part{}-{}-",
+            self.day,
+            self.part,
+            sanitize(&self.source)
+        )
+    }
+
+    fn file_name(&self) -> String {
+        format!(
+            "{}v{}-{:016x}.txt",
+            self.prefix(),
+            sanitize(&self.version),
+            self.input_hash
+        )
+    }
+}
+
+/// Keeps file names portable whatever a plugin calls itself
+fn sanitize(s: &str) -> String {
+    s.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '_' || c == '.' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// A 64 bit FNV-1a hash. Unlike `DefaultHasher` it is the same on every
+/// build, which matters for keys that are kept on disk.
+pub fn hash_bytes(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, b| {
+        (hash ^ *b as u64).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+pub struct AnswerCache {
+    dir: PathBuf,
+}
+
+impl AnswerCache {
+    pub fn new(dir: &Path) -> Self {
+        AnswerCache {
+            dir: dir.to_path_buf(),
+        }
+    }
+
+    fn year_dir(&self, year: u16) -> PathBuf {
+        self.dir.join(year.to_string())
+    }
+
+    pub fn get(&self, key: &CacheKey) -> Option<String> {
+        fs::read_to_string(self.year_dir(key.year).join(key.file_name())).ok()
+    }
+
+    /// Stores an answer, dropping anything cached by other versions of the
+    /// same solver
+    pub fn put(&self, key: &CacheKey, answer: &str) -> Result<()> {
+        let dir = self.year_dir(key.year);
+        fs::create_dir_all(&dir).with_context(|| format!("Could not create {}", dir.display()))?;
+
+        let prefix = key.prefix();
+        let version = format!("{}v{}-", prefix, sanitize(&key.version));
+        for entry in fs::read_dir(&dir)? {
+            let name = entry?.file_name();
+            let name = name.to_string_lossy();
+            if name.starts_with(&prefix) && !name.starts_with(&version) {
+                fs::remove_file(dir.join(name.as_ref()))?;
+            }
+        }
+
+        let path = dir.join(key.file_name());
+        fs::write(&path, answer).with_context(|| format!("Could not write {}", path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(version: &str, input: &str) -> CacheKey {
+        CacheKey {
+            year: 2022,
+            day: 10,
+            part: 2,
+            source: "built-in".to_string(),
+            version: version.to_string(),
+            input_hash: hash_bytes(input.as_bytes()),
+        }
+    }
+
+    #[test]
+    fn test_hash_bytes() {
+        assert_eq!(0xcbf29ce484222325, hash_bytes(b""));
+        assert_eq!(0xaf63dc4c8601ec8c, hash_bytes(b"a"));
+        assert_ne!(hash_bytes(b"noop\naddx 3"), hash_bytes(b"noop\naddx 4"));
+    }
+
+    #[test]
+    fn test_get_and_put() {
+        let dir = std::env::temp_dir().join(format!("aoc-cache-test-{}", std::process::id()));
+        let cache = AnswerCache::new(&dir);
+
+        assert_eq!(None, cache.get(&key("1", "input")));
+
+        cache.put(&key("1", "input"), "##..\n#..#").unwrap();
+        cache.put(&key("1", "other input"), "..##").unwrap();
+        assert_eq!(
+            Some("##..\n#..#".to_string()),
+            cache.get(&key("1", "input"))
+        );
+        assert_eq!(None, cache.get(&key("2", "input")));
+
+        // A new version of the solver replaces everything the old one cached
+        cache.put(&key("2", "input"), "#..#").unwrap();
+        assert_eq!(None, cache.get(&key("1", "input")));
+        assert_eq!(None, cache.get(&key("1", "other input")));
+        assert_eq!(Some("#..#".to_string()), cache.get(&key("2", "input")));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
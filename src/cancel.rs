@@ -0,0 +1,142 @@
+//! Cooperative cancellation for long-running solvers.
+//!
+//! The harness installs a [`CancelToken`] with [`run_with`] before calling a
+//! solver, and the solver calls [`checkpoint`] in its main loop. Once the
+//! token is cancelled or its deadline passes, the checkpoint returns a
+//! [`Cancelled`] error carrying the progress the solver reported, which the
+//! solver hands back with `?`. Solvers that never call [`checkpoint`] (and
+//! plugins, which run outside the harness) cannot be stopped this way.
+
+use std::cell::RefCell;
+use std::fmt::Display;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken {
+    cancelled: Arc<AtomicBool>,
+    deadline: Option<Instant>,
+}
+
+impl CancelToken {
+    pub fn new() -> Self {
+        CancelToken::default()
+    }
+
+    /// A token that cancels itself once `timeout` has passed
+    pub fn with_timeout(timeout: Duration) -> Self {
+        CancelToken {
+            cancelled: Arc::default(),
+            deadline: Some(Instant::now() + timeout),
+        }
+    }
+
+    /// Cancels the token, and every clone of it
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    fn timed_out(&self) -> bool {
+        self.deadline.is_some_and(|d| Instant::now() >= d)
+    }
+}
+
+/// Returned from [`checkpoint`] once the token is cancelled
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cancelled {
+    pub timed_out: bool,
+
+    /// How far the solver got, as described by the solver
+    pub progress: String,
+}
+
+impl Display for Cancelled {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let reason = if self.timed_out {
+            "Timed out"
+        } else {
+            "Cancelled"
+        };
+        write!(f, "{} after {}", reason, self.progress)
+    }
+}
+
+impl std::error::Error for Cancelled {}
+
+thread_local! {
+    static TOKEN: RefCell<Option<CancelToken>> = const { RefCell::new(None) };
+}
+
+/// Runs `f` with `token` installed for the checkpoints on this thread
+pub fn run_with<T>(token: CancelToken, f: impl FnOnce() -> T) -> T {
+    let previous = TOKEN.with(|t| t.replace(Some(token)));
+    let result = f();
+    TOKEN.with(|t| t.replace(previous));
+
+    result
+}
+
+/// Fails with [`Cancelled`] once the installed token is cancelled or past its
+/// deadline. `progress` is only called then, so it can format freely.
+pub fn checkpoint<F>(progress: F) -> Result<(), Cancelled>
+where
+    F: FnOnce() -> String,
+{
+    TOKEN.with(|t| match &*t.borrow() {
+        Some(token) if token.cancelled.load(Ordering::Relaxed) => Err(Cancelled {
+            timed_out: false,
+            progress: progress(),
+        }),
+        Some(token) if token.timed_out() => Err(Cancelled {
+            timed_out: true,
+            progress: progress(),
+        }),
+        _ => Ok(()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn count_to(n: usize) -> Result<usize, Cancelled> {
+        for i in 0..n {
+            checkpoint(|| format!("{}/{}", i, n))?;
+        }
+        Ok(n)
+    }
+
+    #[test]
+    fn test_no_token() {
+        assert_eq!(Ok(10), count_to(10));
+    }
+
+    #[test]
+    fn test_cancel() {
+        let token = CancelToken::new();
+        token.cancel();
+
+        let result = run_with(token, || count_to(10));
+
+        assert_eq!(
+            Err(Cancelled {
+                timed_out: false,
+                progress: "0/10".to_string()
+            }),
+            result
+        );
+        // the token is gone once run_with returns
+        assert_eq!(Ok(10), count_to(10));
+    }
+
+    #[test]
+    fn test_timeout() {
+        let token = CancelToken::with_timeout(Duration::ZERO);
+
+        let err = run_with(token, || count_to(10)).unwrap_err();
+
+        assert!(err.timed_out);
+        assert_eq!("Timed out after 0/10", err.to_string());
+    }
+}
@@ -0,0 +1,128 @@
+//! Defaults for the command line, read from `aoc.toml`.
+//!
+//! Every field is optional and mirrors a command line option of the same name,
+//! which overrides it. `[params.dayN]` tables hold the [`crate::params`] for a
+//! day:
+//!
+//! ```toml
+//! inputs = "resources"
+//! quiet = true
+//! output = "json"
+//! timeout = 60
+//!
+//! [params.day15]
+//! row = 10
+//! max = 20
+//! ```
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+
+use crate::params::Params;
+
+/// Where the config is looked for when `--config` is not given
+pub const CONFIG_FILE: &str = "./aoc.toml";
+
+#[derive(Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Config {
+    pub year: Option<u16>,
+
+    /// The directory the puzzle inputs are read from
+    pub inputs: Option<PathBuf>,
+    pub quiet: Option<bool>,
+    pub output: Option<String>,
+    pub timeout: Option<u64>,
+
+    /// `false` does the same as `--no-cache`
+    pub cache: Option<bool>,
+    pub memory: Option<bool>,
+    pub plugins: Option<Vec<PathBuf>>,
+
+    pub every: Option<usize>,
+    pub format: Option<String>,
+    pub cell_size: Option<usize>,
+    pub palette: Option<String>,
+    pub delay: Option<u16>,
+
+    /// Parameters by day, keyed `day1`, `day2`, ...
+    pub params: BTreeMap<String, Params>,
+}
+
+impl Config {
+    /// Reads the config from `path`, or from [`CONFIG_FILE`] if there is one
+    pub fn load(path: Option<&Path>) -> Result<Self> {
+        let path = match path {
+            Some(path) => path,
+            None if Path::new(CONFIG_FILE).exists() => Path::new(CONFIG_FILE),
+            None => return Ok(Config::default()),
+        };
+
+        let text = fs::read_to_string(path)
+            .with_context(|| format!("Could not read {}", path.display()))?;
+        Config::parse(&text).with_context(|| format!("Could not parse {}", path.display()))
+    }
+
+    pub fn parse(text: &str) -> Result<Self> {
+        Ok(toml::from_str(text)?)
+    }
+
+    /// The parameters configured for a day
+    pub fn params(&self, day: u8) -> Params {
+        self.params
+            .get(&format!("day{}", day))
+            .cloned()
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let config = Config::parse(
+            r#"
+            inputs = "examples"
+            output = "json"
+            timeout = 60
+            cache = false
+            cell-size = 8
+
+            [params.day15]
+            row = 10
+            max = 20
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(Some(PathBuf::from("examples")), config.inputs);
+        assert_eq!(Some("json".to_string()), config.output);
+        assert_eq!(Some(60), config.timeout);
+        assert_eq!(Some(false), config.cache);
+        assert_eq!(Some(8), config.cell_size);
+        assert_eq!(None, config.quiet);
+        assert_eq!(
+            Params::from([("max".to_string(), 20), ("row".to_string(), 10)]),
+            config.params(15)
+        );
+        assert!(config.params(14).is_empty());
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(Config::parse("timeout = \"soon\"").is_err());
+        assert!(Config::parse("no-such-option = 1").is_err());
+        assert!(Config::parse("[params.day15]\nrow = \"ten\"").is_err());
+    }
+
+    #[test]
+    fn test_load_missing_file() {
+        assert!(Config::load(Some(Path::new("no/such/aoc.toml"))).is_err());
+    }
+}
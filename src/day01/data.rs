@@ -0,0 +1,273 @@
+//! The elves and their snacks.
+//!
+//! Besides the puzzle's format, one number per line and a blank line between
+//! elves, the parser takes an extended format where an elf can start with a
+//! `Name:` header and each item can have a label after its calories:
+//!
+//! ```text
+//! Alice:
+//! 1000 apple
+//! 2000 trail mix
+//!
+//! 4000
+//! ```
+
+use std::fmt::Display;
+use std::str::FromStr;
+
+use anyhow::{bail, Context, Result};
+
+/// A snack and its calories
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Item {
+    pub calories: u32,
+    pub label: Option<String>,
+}
+
+impl FromStr for Item {
+    type Err = anyhow::Error;
+
+    /// `1000` or `1000 apple`
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+        let (calories, label) = match s.split_once(char::is_whitespace) {
+            Some((calories, label)) => (calories, Some(label.trim().to_string())),
+            None => (s, None),
+        };
+
+        Ok(Item {
+            calories: calories
+                .parse()
+                .with_context(|| format!("Invalid calories {:?}", calories))?,
+            label,
+        })
+    }
+}
+
+impl Display for Item {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.label {
+            Some(label) => write!(f, "{} {}", self.calories, label),
+            None => write!(f, "{}", self.calories),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Elf {
+    /// The elf's position in the input, 0 for the first
+    pub id: usize,
+    pub name: Option<String>,
+    pub items: Vec<Item>,
+}
+
+impl Elf {
+    /// In `u64`, which the items' `u32` calories cannot overflow
+    pub fn calories(&self) -> u64 {
+        self.items.iter().map(|item| u64::from(item.calories)).sum()
+    }
+}
+
+/// The elf's items, after its name if it has one, in the format they are
+/// parsed from
+impl Display for Elf {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(name) = &self.name {
+            writeln!(f, "{}:", name)?;
+        }
+        for item in &self.items {
+            writeln!(f, "{}", item)?;
+        }
+        Ok(())
+    }
+}
+
+/// A line of the input
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Line {
+    /// Ends the current elf
+    Blank,
+    Name(String),
+    Item(Item),
+}
+
+/// A line that parses as an item is one, even when its label ends in a ':'
+/// like `1000 note:`. Anything else ending in a ':' is a name.
+pub fn parse_line(line: &str) -> Result<Line> {
+    let line = line.trim();
+    if line.is_empty() {
+        return Ok(Line::Blank);
+    }
+
+    let item = line.parse::<Item>();
+    match line.strip_suffix(':') {
+        Some(name) if item.is_err() => {
+            let name = name.trim();
+            if name.is_empty() {
+                bail!("Missing the elf's name before the ':'");
+            }
+            Ok(Line::Name(name.to_string()))
+        }
+        _ => Ok(Line::Item(item?)),
+    }
+}
+
+/// Collects the lines of one elf at a time. [`parse_elves`] and the
+/// streaming [`super::top_elves`] both feed their lines through it.
+#[derive(Debug, Default)]
+pub struct ElfBuilder {
+    next_id: usize,
+    name: Option<String>,
+    items: Vec<Item>,
+}
+
+impl ElfBuilder {
+    /// Adds a line, returning the elf it finished, if any
+    pub fn push(&mut self, line: Line) -> Result<Option<Elf>> {
+        match line {
+            Line::Blank => return Ok(self.finish()),
+            Line::Name(name) => {
+                if self.name.is_some() || !self.items.is_empty() {
+                    bail!("The name {} must start a new elf", name);
+                }
+                self.name = Some(name);
+            }
+            Line::Item(item) => self.items.push(item),
+        }
+
+        Ok(None)
+    }
+
+    /// The elf being built, once the input ends. A group of lines is an elf
+    /// when it has a name or at least one item.
+    pub fn finish(&mut self) -> Option<Elf> {
+        if self.name.is_none() && self.items.is_empty() {
+            return None;
+        }
+
+        let elf = Elf {
+            id: self.next_id,
+            name: self.name.take(),
+            items: std::mem::take(&mut self.items),
+        };
+        self.next_id += 1;
+
+        Some(elf)
+    }
+}
+
+/// Parses one group of lines per elf, with a blank line between elves
+pub fn parse_elves(input: &str) -> Result<Vec<Elf>> {
+    let mut builder = ElfBuilder::default();
+    let mut elves = Vec::new();
+
+    for (line_no, line) in input.lines().enumerate() {
+        let elf = parse_line(line)
+            .and_then(|line| builder.push(line))
+            .with_context(|| format!("Invalid line {}", line_no))?;
+        elves.extend(elf);
+    }
+    elves.extend(builder.finish());
+
+    Ok(elves)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(calories: u32, label: Option<&str>) -> Item {
+        Item {
+            calories,
+            label: label.map(String::from),
+        }
+    }
+
+    #[test]
+    fn test_parse_elves() {
+        let elves = parse_elves("1000\n2000\n\n\n4000\n\n5000\n6000\n").unwrap();
+
+        assert_eq!(3, elves.len());
+        assert_eq!(
+            Elf {
+                id: 1,
+                name: None,
+                items: vec![item(4000, None)]
+            },
+            elves[1]
+        );
+        assert_eq!(11000, elves[2].calories());
+    }
+
+    #[test]
+    fn test_parse_extended() {
+        let input = "Alice:
+1000 apple
+2000 trail  mix
+
+4000
+
+Bob:
+";
+        let elves = parse_elves(input).unwrap();
+
+        assert_eq!(
+            vec![
+                Elf {
+                    id: 0,
+                    name: Some("Alice".to_string()),
+                    items: vec![item(1000, Some("apple")), item(2000, Some("trail  mix"))]
+                },
+                Elf {
+                    id: 1,
+                    name: None,
+                    items: vec![item(4000, None)]
+                },
+                Elf {
+                    id: 2,
+                    name: Some("Bob".to_string()),
+                    items: vec![]
+                },
+            ],
+            elves
+        );
+        assert_eq!(
+            "Alice:\n1000 apple\n2000 trail  mix\n",
+            elves[0].to_string()
+        );
+    }
+
+    #[test]
+    fn test_label_ending_in_colon() {
+        assert_eq!(
+            Line::Item(item(1000, Some("note:"))),
+            parse_line("1000 note:").unwrap()
+        );
+        assert_eq!(Line::Name("2022".to_string()), parse_line("2022:").unwrap());
+    }
+
+    #[test]
+    fn test_calories_past_u32() {
+        let elves = parse_elves("4000000000\n4000000000\n").unwrap();
+
+        assert_eq!(8_000_000_000, elves[0].calories());
+    }
+
+    #[test]
+    fn test_display_round_trip() {
+        let input = "Alice:\n1000 apple\n2000 note:\n\n4000 pie\n";
+        let elves = parse_elves(input).unwrap();
+        let text = elves.iter().map(Elf::to_string).collect::<Vec<_>>();
+
+        assert_eq!(elves, parse_elves(&text.join("\n")).unwrap());
+    }
+
+    #[test]
+    fn test_parse_elves_invalid() {
+        assert!(parse_elves("1000\nlots\n").is_err());
+        assert!(parse_elves("1000\nBob:\n").is_err());
+        assert!(parse_elves("Alice:\nBob:\n").is_err());
+        assert!(parse_elves(":\n1000\n").is_err());
+        assert!(parse_elves("").unwrap().is_empty());
+    }
+}
@@ -0,0 +1,238 @@
+use anyhow::{Context, Result};
+use itertools::Itertools;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::fmt::Display;
+use std::io::BufRead;
+
+use crate::params;
+
+pub mod data;
+pub mod rebalance;
+pub mod stats;
+use data::{parse_line, Elf, ElfBuilder};
+
+pub fn solve(part: u8, lines: &str) -> Result<String> {
+    solve_reader(part, lines.as_bytes())
+}
+
+/// Like [`solve`], reading the input a line at a time
+pub fn solve_reader(part: u8, reader: impl BufRead) -> Result<String> {
+    if part == 1 {
+        Ok(part1(reader)?.to_string())
+    } else {
+        Ok(part2(reader)?.to_string())
+    }
+}
+
+fn part2(reader: impl BufRead) -> Result<u64> {
+    println!("Running Day1::part2");
+
+    let top = params::get("top", 3);
+    let n = usize::try_from(top)
+        .ok()
+        .filter(|n| *n >= 1)
+        .with_context(|| format!("Invalid number of elves {}", top))?;
+    let top = top_elves(reader, n)?;
+    let total = top
+        .iter()
+        .try_fold(0u64, |total, elf| total.checked_add(elf.calories))
+        .context("The total calories overflow")?;
+
+    let elves = top.iter().map(ElfTotal::to_string).join(", ");
+    println!(
+        "The top {} Elves ({}) are carrying {} calories.",
+        n, elves, total
+    );
+
+    Ok(total)
+}
+
+fn part1(reader: impl BufRead) -> Result<u64> {
+    println!("Running Day1::part1");
+
+    let top = top_elves(reader, 1)?;
+    let elf = top.first().context("There are no elves in the input")?;
+
+    println!(
+        "The Elf carrying the most calories ({}) is carrying {} calories.",
+        elf, elf.calories
+    );
+
+    Ok(elf.calories)
+}
+
+/// An elf and the calories it carries
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ElfTotal {
+    /// The elf's [`Elf::id`], its position in the input
+    pub index: usize,
+    pub name: Option<String>,
+    pub calories: u64,
+}
+
+impl ElfTotal {
+    pub fn new(elf: &Elf) -> Self {
+        ElfTotal {
+            index: elf.id,
+            name: elf.name.clone(),
+            calories: elf.calories(),
+        }
+    }
+
+    /// Orders by calories. On a tie the elf that comes first in the input
+    /// ranks higher.
+    fn rank(&self) -> (u64, Reverse<usize>) {
+        (self.calories, Reverse(self.index))
+    }
+}
+
+/// The elf's name, or `#index` for elves without one
+impl Display for ElfTotal {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.name {
+            Some(name) => write!(f, "{}", name),
+            None => write!(f, "#{}", self.index),
+        }
+    }
+}
+
+/// The `n` elves carrying the most calories, most first. Only `n` elves are
+/// kept at a time, so the input can be streamed.
+pub fn top_elves(reader: impl BufRead, n: usize) -> Result<Vec<ElfTotal>> {
+    // a min-heap, so the weakest of the current top n is popped first
+    let mut heap = BinaryHeap::new();
+    let mut push = |elf: ElfTotal| {
+        heap.push(Reverse((elf.rank(), elf.name)));
+        if heap.len() > n {
+            heap.pop();
+        }
+    };
+
+    let mut builder = ElfBuilder::default();
+    for (line_no, line) in reader.lines().enumerate() {
+        let elf = line
+            .with_context(|| format!("Could not read line {}", line_no))
+            .and_then(|line| parse_line(&line))
+            .and_then(|line| builder.push(line))
+            .with_context(|| format!("Invalid line {}", line_no))?;
+        if let Some(elf) = elf {
+            push(ElfTotal::new(&elf));
+        }
+    }
+    // the last elf is not followed by a blank line
+    if let Some(elf) = builder.finish() {
+        push(ElfTotal::new(&elf));
+    }
+
+    Ok(heap
+        .into_sorted_vec()
+        .into_iter()
+        .map(|Reverse(((calories, Reverse(index)), name))| ElfTotal {
+            index,
+            name,
+            calories,
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::day01::*;
+
+    const EXAMPLE: &str = "1000
+2000
+3000
+
+4000
+
+5000
+6000
+
+7000
+8000
+9000
+
+10000";
+
+    fn elf(index: usize, calories: u64) -> ElfTotal {
+        ElfTotal {
+            index,
+            name: None,
+            calories,
+        }
+    }
+
+    #[test]
+    fn test_example() {
+        assert_eq!(24000, part1(EXAMPLE.as_bytes()).unwrap());
+        assert_eq!(45000, part2(EXAMPLE.as_bytes()).unwrap());
+    }
+
+    #[test]
+    fn test_part2_top() {
+        let params = params::Params::from([("top".to_string(), 2)]);
+        assert_eq!(
+            35000,
+            params::run_with(&params, || part2(EXAMPLE.as_bytes())).unwrap()
+        );
+
+        for top in [0, -1] {
+            let params = params::Params::from([("top".to_string(), top)]);
+            assert_eq!(
+                format!("Invalid number of elves {}", top),
+                params::run_with(&params, || part2(EXAMPLE.as_bytes()))
+                    .unwrap_err()
+                    .to_string()
+            );
+        }
+    }
+
+    #[test]
+    fn test_top_elves() {
+        assert_eq!(
+            vec![elf(3, 24000), elf(2, 11000), elf(4, 10000)],
+            top_elves(EXAMPLE.as_bytes(), 3).unwrap()
+        );
+        assert_eq!(5, top_elves(EXAMPLE.as_bytes(), 10).unwrap().len());
+        assert!(top_elves(EXAMPLE.as_bytes(), 0).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_top_elves_ties() {
+        let input = "300\n\n100\n200\n\n300\n\n50\n\n\n300\n";
+
+        assert_eq!(
+            vec![elf(0, 300), elf(1, 300), elf(2, 300)],
+            top_elves(input.as_bytes(), 3).unwrap()
+        );
+        assert_eq!(
+            vec![elf(0, 300), elf(1, 300)],
+            top_elves(input.as_bytes(), 2).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_top_elves_named() {
+        let input = "Alice:\n1000 apple\n\n3000\n\nBob:\n2000 pie\n500\n";
+        let top = top_elves(input.as_bytes(), 2).unwrap();
+
+        assert_eq!(
+            vec![
+                elf(1, 3000),
+                ElfTotal {
+                    index: 2,
+                    name: Some("Bob".to_string()),
+                    calories: 2500
+                }
+            ],
+            top
+        );
+        assert_eq!("#1, Bob", top.iter().map(ElfTotal::to_string).join(", "));
+    }
+
+    #[test]
+    fn test_top_elves_invalid() {
+        assert!(top_elves("100\nlots\n".as_bytes(), 3).is_err());
+    }
+}
@@ -0,0 +1,340 @@
+//! Moving snacks between elves so the heaviest load is as small as possible.
+//!
+//! Small inputs are searched exhaustively: every item is assigned to every
+//! elf, branch and bound, for the smallest heaviest load and then the fewest
+//! moves. The search gives up after [`EXACT_NODE_LIMIT`] steps and anything
+//! with more than [`EXACT_MAX_ITEMS`] items goes straight to the heuristic.
+//!
+//! The heuristic repeatedly moves one item from the heaviest elf to the
+//! lightest, picking the item that leaves the two closest to even, as long as
+//! that makes the pair lighter than the heaviest load was. Every move makes
+//! the loads more even, so it always stops, but it can stop short of the best
+//! plan: it never swaps items or moves them between two middling elves.
+
+use std::fmt::Display;
+
+use anyhow::{bail, Result};
+
+use super::data::{Elf, Item};
+use super::ElfTotal;
+
+/// Inputs with more items than this are always planned with the heuristic
+pub const EXACT_MAX_ITEMS: usize = 16;
+
+/// How many assignments the exact search tries before giving up
+pub const EXACT_NODE_LIMIT: usize = 2_000_000;
+
+/// Moving one item from one elf to another
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Transfer {
+    pub item: Item,
+    pub from: ElfTotal,
+    pub to: ElfTotal,
+}
+
+impl Display for Transfer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Move {} from {} to {}", self.item, self.from, self.to)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Plan {
+    pub transfers: Vec<Transfer>,
+    pub max_before: u64,
+    pub max_after: u64,
+
+    /// Whether the plan is the best possible, or from the heuristic
+    pub exact: bool,
+}
+
+impl Display for Plan {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "Heaviest load: {} -> {} ({} moves, {})",
+            self.max_before,
+            self.max_after,
+            self.transfers.len(),
+            if self.exact {
+                "best possible"
+            } else {
+                "heuristic"
+            }
+        )?;
+        for transfer in &self.transfers {
+            writeln!(f, "  {}", transfer)?;
+        }
+        Ok(())
+    }
+}
+
+/// Plans at most `max_transfers` moves (any number without a limit) that
+/// bring the heaviest load down as far as possible
+pub fn plan(elves: &[Elf], max_transfers: Option<usize>) -> Result<Plan> {
+    if elves.is_empty() {
+        bail!("There are no elves to rebalance");
+    }
+
+    let items: Vec<(usize, &Item)> = elves
+        .iter()
+        .enumerate()
+        .flat_map(|(idx, elf)| elf.items.iter().map(move |item| (idx, item)))
+        .collect();
+    let limit = max_transfers.unwrap_or(usize::MAX);
+
+    let exact = if items.len() <= EXACT_MAX_ITEMS {
+        exact_moves(elves.len(), &items, limit)
+    } else {
+        None
+    };
+    let is_exact = exact.is_some();
+    let moves = match exact {
+        Some(moves) => moves,
+        None => heuristic_moves(elves, limit),
+    };
+
+    let mut loads: Vec<u64> = elves.iter().map(Elf::calories).collect();
+    let max_before = loads.iter().copied().max().unwrap_or(0);
+    let transfers = moves
+        .into_iter()
+        .map(|(item, from, to)| {
+            loads[from] -= u64::from(item.calories);
+            loads[to] += u64::from(item.calories);
+            Transfer {
+                item,
+                from: ElfTotal::new(&elves[from]),
+                to: ElfTotal::new(&elves[to]),
+            }
+        })
+        .collect();
+
+    Ok(Plan {
+        transfers,
+        max_before,
+        max_after: loads.iter().copied().max().unwrap_or(0),
+        exact: is_exact,
+    })
+}
+
+/// A move of an item, between elves by their position in the slice
+type Move = (Item, usize, usize);
+
+struct Search<'a> {
+    /// (elf the item starts with, item), heaviest first
+    items: Vec<(usize, &'a Item)>,
+    loads: Vec<u64>,
+    assignment: Vec<usize>,
+    limit: usize,
+
+    /// (heaviest load, moves, assignment) of the best plan so far
+    best: Option<(u64, usize, Vec<usize>)>,
+    nodes: usize,
+}
+
+impl Search<'_> {
+    /// Assigns the items from `next` on. Returns false once the search has
+    /// used up its nodes.
+    fn assign(&mut self, next: usize, max: u64, moves: usize) -> bool {
+        self.nodes += 1;
+        if self.nodes > EXACT_NODE_LIMIT {
+            return false;
+        }
+
+        // max and moves only grow further down, so this branch cannot win
+        if let Some((best_max, best_moves, _)) = &self.best {
+            if (max, moves) >= (*best_max, *best_moves) {
+                return true;
+            }
+        }
+
+        let Some(&(origin, item)) = self.items.get(next) else {
+            self.best = Some((max, moves, self.assignment.clone()));
+            return true;
+        };
+
+        // staying put first finds a plan without moves straight away
+        let order = std::iter::once(origin).chain((0..self.loads.len()).filter(|e| *e != origin));
+        for elf in order {
+            let moved = elf != origin;
+            if moved && moves >= self.limit {
+                continue;
+            }
+
+            self.loads[elf] += u64::from(item.calories);
+            self.assignment[next] = elf;
+            let done = self.assign(
+                next + 1,
+                max.max(self.loads[elf]),
+                moves + usize::from(moved),
+            );
+            self.loads[elf] -= u64::from(item.calories);
+
+            if !done {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+/// The best moves, or None when the search takes too long
+fn exact_moves(elf_count: usize, items: &[(usize, &Item)], limit: usize) -> Option<Vec<Move>> {
+    let mut items = items.to_vec();
+    items.sort_by_key(|(elf, item)| (std::cmp::Reverse(item.calories), *elf));
+
+    let mut search = Search {
+        assignment: vec![0; items.len()],
+        items,
+        loads: vec![0; elf_count],
+        limit,
+        best: None,
+        nodes: 0,
+    };
+    if !search.assign(0, 0, 0) {
+        return None;
+    }
+
+    let (_, _, assignment) = search.best?;
+    let mut moves: Vec<Move> = search
+        .items
+        .iter()
+        .zip(assignment)
+        .filter(|((from, _), to)| from != to)
+        .map(|((from, item), to)| ((*item).clone(), *from, to))
+        .collect();
+    moves.sort_by_key(|(item, from, to)| (*from, *to, std::cmp::Reverse(item.calories)));
+
+    Some(moves)
+}
+
+/// Moves from the heaviest elf to the lightest, see the module docs
+fn heuristic_moves(elves: &[Elf], limit: usize) -> Vec<Move> {
+    let mut holdings: Vec<Vec<Item>> = elves.iter().map(|elf| elf.items.clone()).collect();
+    let mut loads: Vec<u64> = elves.iter().map(Elf::calories).collect();
+    let mut moves = Vec::new();
+
+    while moves.len() < limit {
+        // the first of several equally loaded elves, to stay deterministic
+        let heaviest = (0..loads.len()).max_by_key(|e| (loads[*e], std::cmp::Reverse(*e)));
+        let lightest = (0..loads.len()).min_by_key(|e| (loads[*e], *e));
+        let (Some(heaviest), Some(lightest)) = (heaviest, lightest) else {
+            break;
+        };
+        let gap = loads[heaviest] - loads[lightest];
+
+        // moving x leaves the pair at heaviest - x and lightest + x, which
+        // only helps while x < gap, and is most even when 2x is closest to gap
+        let best = holdings[heaviest]
+            .iter()
+            .enumerate()
+            .filter(|(_, item)| item.calories > 0 && u64::from(item.calories) < gap)
+            .min_by_key(|(_, item)| {
+                (
+                    (2 * item.calories as i64 - gap as i64).abs(),
+                    std::cmp::Reverse(item.calories),
+                )
+            })
+            .map(|(idx, _)| idx);
+        let Some(idx) = best else {
+            break;
+        };
+
+        let item = holdings[heaviest].remove(idx);
+        loads[heaviest] -= u64::from(item.calories);
+        loads[lightest] += u64::from(item.calories);
+        holdings[lightest].push(item.clone());
+        moves.push((item, heaviest, lightest));
+    }
+
+    moves
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::day01::data::parse_elves;
+
+    const EXAMPLE: &str = "1000
+2000
+3000
+
+4000
+
+5000
+6000
+
+7000
+8000
+9000
+
+10000";
+
+    #[test]
+    fn test_exact_plan() {
+        let plan = plan(&parse_elves(EXAMPLE).unwrap(), None).unwrap();
+
+        assert!(plan.exact);
+        assert_eq!(24000, plan.max_before);
+        assert_eq!(11000, plan.max_after);
+        // five pairs of 11000, one of which (5000 and 6000) is already together
+        assert_eq!(4, plan.transfers.len());
+    }
+
+    #[test]
+    fn test_exact_plan_with_limit() {
+        let plan = plan(&parse_elves(EXAMPLE).unwrap(), Some(1)).unwrap();
+
+        assert!(plan.exact);
+        assert_eq!(15000, plan.max_after);
+        assert_eq!(1, plan.transfers.len());
+        assert_eq!("Move 9000 from #3 to #0", plan.transfers[0].to_string());
+    }
+
+    #[test]
+    fn test_heuristic() {
+        let elves = parse_elves(EXAMPLE).unwrap();
+        let moves = heuristic_moves(&elves, usize::MAX);
+
+        let moved: Vec<(u32, usize, usize)> = moves
+            .iter()
+            .map(|(item, from, to)| (item.calories, *from, *to))
+            .collect();
+        assert_eq!(vec![(9000, 3, 1), (7000, 3, 0), (3000, 0, 3)], moved);
+
+        assert_eq!(2, heuristic_moves(&elves, 2).len());
+    }
+
+    #[test]
+    fn test_large_input_uses_heuristic() {
+        let input = (1..=40)
+            .map(|c| format!("{}\n", c * 100))
+            .collect::<String>();
+        let elves = parse_elves(&format!("{}\n\n\n0", input)).unwrap();
+
+        let plan = plan(&elves, None).unwrap();
+
+        assert!(!plan.exact);
+        assert_eq!(82000, plan.max_before);
+        assert!(plan.max_after < plan.max_before);
+    }
+
+    #[test]
+    fn test_named_elves() {
+        let elves = parse_elves("Alice:\n5000 pie\n1000 apple\n\nBob:\n").unwrap();
+
+        let plan = plan(&elves, None).unwrap();
+
+        assert_eq!(5000, plan.max_after);
+        assert_eq!(
+            "Move 1000 apple from Alice to Bob",
+            plan.transfers[0].to_string()
+        );
+    }
+
+    #[test]
+    fn test_no_elves() {
+        assert!(plan(&[], None).is_err());
+    }
+}
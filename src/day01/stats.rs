@@ -0,0 +1,234 @@
+use std::fmt::Display;
+
+use anyhow::{bail, Result};
+
+use super::data::Elf;
+use super::ElfTotal;
+
+/// The percentiles in the report
+pub const PERCENTILES: [u8; 5] = [10, 25, 50, 75, 90];
+
+const HISTOGRAM_BUCKETS: u64 = 8;
+const HISTOGRAM_WIDTH: usize = 40;
+
+/// Statistics over the calories the elves carry
+#[derive(Debug, Clone, PartialEq)]
+pub struct CalorieStats {
+    pub count: usize,
+    pub total: u64,
+    pub mean: f64,
+    pub median: f64,
+
+    /// The calories at each of [`PERCENTILES`], by nearest rank
+    pub percentiles: Vec<(u8, u64)>,
+
+    /// The lightest and heaviest elf. On a tie, the first in the input.
+    pub min: ElfTotal,
+    pub max: ElfTotal,
+
+    /// How many items each elf carries, in input order
+    pub item_counts: Vec<usize>,
+
+    /// The number of elves whose load falls in each range, inclusive
+    pub histogram: Vec<(u64, u64, usize)>,
+}
+
+pub fn calorie_stats(elves: &[Elf]) -> Result<CalorieStats> {
+    if elves.is_empty() {
+        bail!("There are no elves to report on");
+    }
+
+    let totals: Vec<ElfTotal> = elves.iter().map(ElfTotal::new).collect();
+
+    let mut sorted: Vec<u64> = totals.iter().map(|t| t.calories).collect();
+    sorted.sort_unstable();
+
+    let count = sorted.len();
+    let total: u64 = sorted.iter().sum();
+    let median = if count % 2 == 1 {
+        sorted[count / 2] as f64
+    } else {
+        (sorted[count / 2 - 1] as f64 + sorted[count / 2] as f64) / 2.0
+    };
+
+    let percentiles = PERCENTILES
+        .iter()
+        .map(|p| {
+            let rank = (*p as usize * count).div_ceil(100).max(1);
+            (*p, sorted[rank - 1])
+        })
+        .collect();
+
+    // min_by_key and max_by_key disagree on ties, so rank by the index too
+    let min = totals
+        .iter()
+        .min_by_key(|t| (t.calories, t.index))
+        .unwrap()
+        .clone();
+    let max = totals
+        .iter()
+        .max_by_key(|t| (t.calories, std::cmp::Reverse(t.index)))
+        .unwrap()
+        .clone();
+
+    Ok(CalorieStats {
+        count,
+        total,
+        mean: total as f64 / count as f64,
+        median,
+        percentiles,
+        min,
+        max,
+        item_counts: elves.iter().map(|elf| elf.items.len()).collect(),
+        histogram: histogram(&sorted),
+    })
+}
+
+/// Splits the range of loads into equal buckets and counts the elves in each
+fn histogram(sorted: &[u64]) -> Vec<(u64, u64, usize)> {
+    let (lowest, highest) = (sorted[0], sorted[sorted.len() - 1]);
+    let width = (highest - lowest) / HISTOGRAM_BUCKETS + 1;
+
+    (0..HISTOGRAM_BUCKETS)
+        .map(|bucket| {
+            let from = lowest + bucket * width;
+            let to = from + width - 1;
+            let count = sorted.iter().filter(|c| (from..=to).contains(*c)).count();
+            (from, to, count)
+        })
+        .filter(|(from, _, _)| *from <= highest)
+        .collect()
+}
+
+impl Display for CalorieStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Elves:   {}", self.count)?;
+        writeln!(f, "Total:   {} calories", self.total)?;
+        writeln!(f, "Mean:    {:.1}", self.mean)?;
+        writeln!(f, "Median:  {:.1}", self.median)?;
+        for (p, calories) in &self.percentiles {
+            writeln!(f, "p{:<7} {}", p, calories)?;
+        }
+        writeln!(f, "Min:     {} ({})", self.min.calories, self.min)?;
+        writeln!(f, "Max:     {} ({})", self.max.calories, self.max)?;
+
+        let items = &self.item_counts;
+        writeln!(
+            f,
+            "Items:   {} to {} per elf, {:.1} on average",
+            items.iter().min().unwrap_or(&0),
+            items.iter().max().unwrap_or(&0),
+            items.iter().sum::<usize>() as f64 / items.len().max(1) as f64
+        )?;
+
+        writeln!(f, "Loads:")?;
+        let most = self.histogram.iter().map(|b| b.2).max().unwrap_or(0).max(1);
+        let label_width = self
+            .histogram
+            .last()
+            .map_or(0, |(_, to, _)| to.to_string().len());
+        for (from, to, count) in &self.histogram {
+            let bar = "#".repeat((count * HISTOGRAM_WIDTH).div_ceil(most));
+            writeln!(
+                f,
+                "  {:>w$} - {:>w$} | {} {}",
+                from,
+                to,
+                bar,
+                count,
+                w = label_width
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::day01::data::parse_elves;
+
+    const EXAMPLE: &str = "1000
+2000
+3000
+
+4000
+
+5000
+6000
+
+7000
+8000
+9000
+
+10000";
+
+    #[test]
+    fn test_calorie_stats() {
+        let stats = calorie_stats(&parse_elves(EXAMPLE).unwrap()).unwrap();
+
+        assert_eq!(5, stats.count);
+        assert_eq!(55000, stats.total);
+        assert_eq!(11000.0, stats.mean);
+        assert_eq!(10000.0, stats.median);
+        assert_eq!(
+            vec![
+                (10, 4000),
+                (25, 6000),
+                (50, 10000),
+                (75, 11000),
+                (90, 24000)
+            ],
+            stats.percentiles
+        );
+        assert_eq!(
+            ElfTotal {
+                index: 1,
+                name: None,
+                calories: 4000
+            },
+            stats.min
+        );
+        assert_eq!(
+            ElfTotal {
+                index: 3,
+                name: None,
+                calories: 24000
+            },
+            stats.max
+        );
+        assert_eq!(vec![3, 1, 2, 3, 1], stats.item_counts);
+        assert_eq!(5, stats.histogram.iter().map(|b| b.2).sum::<usize>());
+        assert_eq!((4000, 6500, 2), stats.histogram[0]);
+    }
+
+    #[test]
+    fn test_histogram_up_to_u32_max() {
+        let buckets = histogram(&[0, u32::MAX as u64]);
+
+        assert_eq!((0, 536870911, 1), buckets[0]);
+        assert_eq!((3758096384, 4294967295, 1), buckets[7]);
+        assert_eq!(2, buckets.iter().map(|b| b.2).sum::<usize>());
+    }
+
+    #[test]
+    fn test_median_of_even_count() {
+        let stats = calorie_stats(&parse_elves("1\n\n2\n\n3\n\n10").unwrap()).unwrap();
+
+        assert_eq!(2.5, stats.median);
+    }
+
+    #[test]
+    fn test_ties_go_to_the_first_elf() {
+        let stats = calorie_stats(&parse_elves("5\n\n9\n\n5\n\n9").unwrap()).unwrap();
+
+        assert_eq!(0, stats.min.index);
+        assert_eq!(1, stats.max.index);
+    }
+
+    #[test]
+    fn test_no_elves() {
+        assert!(calorie_stats(&[]).is_err());
+    }
+}
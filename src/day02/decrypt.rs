@@ -0,0 +1,200 @@
+//! Reading the second column every way it could mean.
+//!
+//! Without the elf's explanation `X`, `Y` and `Z` could stand for the three
+//! shapes or the three outcomes in any order. Every one of those mappings is
+//! scored, and the ones that match a known total, such as the one a teammate
+//! got, are the candidates for what the elf meant.
+
+use std::fmt::Display;
+use std::io::BufRead;
+
+use anyhow::{Context, Result};
+use itertools::Itertools;
+
+use super::for_each_round;
+use super::game::{Column, Outcome, Shape};
+use super::rules::Rules;
+
+/// What `X`, `Y` and `Z` stand for, in that order
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mapping {
+    Shapes([Shape; 3]),
+    Outcomes([Outcome; 3]),
+}
+
+impl Mapping {
+    /// The six shape mappings then the six outcome mappings, each starting
+    /// with the puzzle's
+    pub fn all() -> Vec<Mapping> {
+        let shapes = Shape::ALL
+            .into_iter()
+            .permutations(3)
+            .map(|p| Mapping::Shapes([p[0], p[1], p[2]]));
+        let outcomes = Outcome::ALL
+            .into_iter()
+            .permutations(3)
+            .map(|p| Mapping::Outcomes([p[0], p[1], p[2]]));
+
+        shapes.chain(outcomes).collect()
+    }
+
+    /// The score of a round against `them` with `column` in the guide, by the
+    /// puzzle's [`Rules::default`]
+    pub fn score(&self, rules: &Rules, them: Shape, column: Column) -> u64 {
+        match self {
+            Mapping::Shapes(shapes) => {
+                rules.score_as_shape(them as usize, shapes[column as usize] as usize)
+            }
+            Mapping::Outcomes(outcomes) => {
+                rules.score_as_outcome(them as usize, outcomes[column as usize])
+            }
+        }
+    }
+}
+
+/// `X=Rock Y=Paper Z=Scissors`
+impl Display for Mapping {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let meanings = match self {
+            Mapping::Shapes(shapes) => shapes.map(|s| format!("{:?}", s)),
+            Mapping::Outcomes(outcomes) => outcomes.map(|o| format!("{:?}", o)),
+        };
+        let pairs = Column::ALL
+            .iter()
+            .zip(meanings)
+            .map(|(column, meaning)| format!("{:?}={}", column, meaning));
+
+        write!(f, "{}", pairs.format(" "))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Decryption {
+    /// Every mapping and the guide's total under it, in [`Mapping::all`] order
+    pub scores: Vec<(Mapping, u64)>,
+    pub target: Option<u64>,
+}
+
+impl Decryption {
+    /// The mappings that score the target
+    pub fn matches(&self) -> Vec<Mapping> {
+        self.scores
+            .iter()
+            .filter(|(_, total)| Some(*total) == self.target)
+            .map(|(mapping, _)| *mapping)
+            .collect()
+    }
+}
+
+impl Display for Decryption {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (mapping, total) in &self.scores {
+            writeln!(f, "{:<30} {}", mapping.to_string(), total)?;
+        }
+
+        if let Some(target) = self.target {
+            let matches = self.matches();
+            if matches.is_empty() {
+                writeln!(f, "No mapping scores {}", target)?;
+            } else {
+                writeln!(f, "Scoring {}:", target)?;
+                for mapping in matches {
+                    writeln!(f, "  {}", mapping)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// How many rounds had each second column against each shape, by [`Shape`]
+/// then [`Column`] as indices
+pub type Tally = [[u64; 3]; 3];
+
+/// Scores the guide under every mapping. The rounds are tallied first, so the
+/// guide is read once however many mappings there are.
+pub fn decrypt(reader: impl BufRead, target: Option<u64>) -> Result<Decryption> {
+    let mut tally: Tally = [[0; 3]; 3];
+    for_each_round(reader, |round| {
+        tally[round.them as usize][round.column as usize] += 1;
+    })?;
+
+    score_tally(&tally, target)
+}
+
+/// Scores the tallied rounds under every mapping
+pub fn score_tally(tally: &Tally, target: Option<u64>) -> Result<Decryption> {
+    let rules = Rules::default();
+    let mut scores = Vec::new();
+    for mapping in Mapping::all() {
+        let mut total: u64 = 0;
+        for them in Shape::ALL {
+            for column in Column::ALL {
+                total = tally[them as usize][column as usize]
+                    .checked_mul(mapping.score(&rules, them, column))
+                    .and_then(|score| total.checked_add(score))
+                    .with_context(|| format!("The total score overflows for {}", mapping))?;
+            }
+        }
+        scores.push((mapping, total));
+    }
+
+    Ok(Decryption { scores, target })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "A Y
+B X
+C Z";
+
+    #[test]
+    fn test_all_mappings() {
+        let mappings = Mapping::all();
+
+        assert_eq!(12, mappings.len());
+        assert_eq!("X=Rock Y=Paper Z=Scissors", mappings[0].to_string());
+        assert_eq!("X=Lose Y=Draw Z=Win", mappings[6].to_string());
+    }
+
+    #[test]
+    fn test_decrypt() {
+        let decryption = decrypt(EXAMPLE.as_bytes(), Some(15)).unwrap();
+
+        assert_eq!(15, decryption.scores[0].1);
+        assert_eq!(12, decryption.scores[6].1);
+        // three rounds say little: X=Paper Y=Rock Z=Scissors, say, draws all three
+        let matches = decryption.matches();
+        assert_eq!(8, matches.len());
+        assert!(matches.contains(&Mapping::all()[0]));
+        assert!(matches.contains(&Mapping::Shapes([
+            Shape::Paper,
+            Shape::Rock,
+            Shape::Scissors
+        ])));
+    }
+
+    #[test]
+    fn test_no_match() {
+        let decryption = decrypt(EXAMPLE.as_bytes(), Some(1)).unwrap();
+
+        assert!(decryption.matches().is_empty());
+        assert!(decryption.to_string().ends_with("No mapping scores 1\n"));
+    }
+
+    #[test]
+    fn test_large_tally() {
+        // more rounds than fit in a u32, all of them rock against rock
+        let mut tally: Tally = [[0; 3]; 3];
+        tally[Shape::Rock as usize][Column::X as usize] = 1 << 32;
+
+        let decryption = score_tally(&tally, None).unwrap();
+        assert_eq!(4 << 32, decryption.scores[0].1);
+
+        tally[Shape::Rock as usize][Column::X as usize] = u64::MAX / 2;
+        assert!(score_tally(&tally, None).is_err());
+    }
+}
@@ -0,0 +1,145 @@
+//! Rock, paper, scissors and the lines of the strategy guide.
+//!
+//! A round scores the shape you play (1 for rock, 2 for paper, 3 for
+//! scissors) plus the outcome (0 for a loss, 3 for a draw, 6 for a win).
+//! Each line of the strategy guide is the opponent's shape, `A`, `B` or `C`,
+//! and a second column, `X`, `Y` or `Z`, whose meaning part 1 and part 2
+//! disagree on. How the shapes play against each other, and so the scores,
+//! is left to [`super::rules::Rules`].
+
+use std::str::FromStr;
+
+use anyhow::{bail, Result};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Shape {
+    Rock,
+    Paper,
+    Scissors,
+}
+
+impl Shape {
+    pub const ALL: [Shape; 3] = [Shape::Rock, Shape::Paper, Shape::Scissors];
+
+    pub fn score(self) -> u32 {
+        match self {
+            Shape::Rock => 1,
+            Shape::Paper => 2,
+            Shape::Scissors => 3,
+        }
+    }
+}
+
+/// The opponent's column: `A`, `B` or `C`
+impl FromStr for Shape {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "A" => Ok(Shape::Rock),
+            "B" => Ok(Shape::Paper),
+            "C" => Ok(Shape::Scissors),
+            _ => bail!("Invalid shape {:?}, expected A, B or C", s),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Outcome {
+    Lose,
+    Draw,
+    Win,
+}
+
+impl Outcome {
+    pub const ALL: [Outcome; 3] = [Outcome::Lose, Outcome::Draw, Outcome::Win];
+
+    pub fn score(self) -> u32 {
+        match self {
+            Outcome::Lose => 0,
+            Outcome::Draw => 3,
+            Outcome::Win => 6,
+        }
+    }
+}
+
+/// The second column of the guide, before deciding what it means
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Column {
+    X,
+    Y,
+    Z,
+}
+
+impl Column {
+    pub const ALL: [Column; 3] = [Column::X, Column::Y, Column::Z];
+
+    /// Part 1's reading: the shape to play
+    pub fn shape(self) -> Shape {
+        match self {
+            Column::X => Shape::Rock,
+            Column::Y => Shape::Paper,
+            Column::Z => Shape::Scissors,
+        }
+    }
+
+    /// Part 2's reading: how the round has to end
+    pub fn outcome(self) -> Outcome {
+        match self {
+            Column::X => Outcome::Lose,
+            Column::Y => Outcome::Draw,
+            Column::Z => Outcome::Win,
+        }
+    }
+}
+
+impl FromStr for Column {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "X" => Ok(Column::X),
+            "Y" => Ok(Column::Y),
+            "Z" => Ok(Column::Z),
+            _ => bail!("Invalid column {:?}, expected X, Y or Z", s),
+        }
+    }
+}
+
+/// A line of the strategy guide
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Round {
+    pub them: Shape,
+    pub column: Column,
+}
+
+impl FromStr for Round {
+    type Err = anyhow::Error;
+
+    /// `A Y`
+    fn from_str(s: &str) -> Result<Self> {
+        let mut iter = s.split_whitespace();
+        let (Some(them), Some(column), None) = (iter.next(), iter.next(), iter.next()) else {
+            bail!("Expected two columns in {:?}", s);
+        };
+
+        Ok(Round {
+            them: them.parse()?,
+            column: column.parse()?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_invalid() {
+        assert!("A".parse::<Round>().is_err());
+        assert!("A Y Z".parse::<Round>().is_err());
+        assert!("D Y".parse::<Round>().is_err());
+        assert!("A W".parse::<Round>().is_err());
+        assert!("a y".parse::<Round>().is_err());
+    }
+}
@@ -0,0 +1,83 @@
+use anyhow::{Context, Result};
+use std::io::BufRead;
+
+pub mod decrypt;
+pub mod game;
+pub mod opponent;
+pub mod rules;
+use game::Round;
+use rules::{Reading, Rules};
+
+pub fn solve(part: u8, lines: &str) -> Result<String> {
+    solve_reader(part, lines.as_bytes())
+}
+
+/// Like [`solve`], reading the input a line at a time
+pub fn solve_reader(part: u8, reader: impl BufRead) -> Result<String> {
+    if part == 1 {
+        Ok(part1(reader)?.to_string())
+    } else {
+        Ok(part2(reader)?.to_string())
+    }
+}
+
+/// Calls `f` with each round of the guide in order, skipping blank lines
+pub fn for_each_round(reader: impl BufRead, mut f: impl FnMut(Round)) -> Result<()> {
+    for (line_no, line) in reader.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let round: Round = line
+            .parse()
+            .with_context(|| format!("Invalid line {}", line_no))?;
+        f(round);
+    }
+
+    Ok(())
+}
+
+fn part2(reader: impl BufRead) -> Result<u64> {
+    println!("Running day2::part2");
+
+    let total = Rules::default().total(reader, Reading::Outcome)?;
+
+    println!("Total points = {}", total);
+    Ok(total)
+}
+
+fn part1(reader: impl BufRead) -> Result<u64> {
+    println!("Running day2::part1");
+
+    let total = Rules::default().total(reader, Reading::Shape)?;
+
+    println!("Total points = {}", total);
+    Ok(total)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "A Y
+B X
+C Z";
+
+    #[test]
+    fn test_part1_example() {
+        assert_eq!(15, part1(EXAMPLE.as_bytes()).unwrap());
+    }
+
+    #[test]
+    fn test_part2_example() {
+        assert_eq!(12, part2(EXAMPLE.as_bytes()).unwrap());
+    }
+
+    #[test]
+    fn test_malformed_line() {
+        let err = part1("A Y\nB Q\n".as_bytes()).unwrap_err();
+
+        assert_eq!("Invalid line 1", err.to_string());
+    }
+}
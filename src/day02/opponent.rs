@@ -0,0 +1,244 @@
+//! The guide as a record of the opponent's moves, and how to beat them.
+//!
+//! Two models are fitted to the first column. The frequency model is how often
+//! the opponent plays each shape. The Markov model is how often they play
+//! each shape after each shape, and falls back to the frequency model for the
+//! first round and after shapes that are never followed by another. Against
+//! each model the counter-strategy plays the shape with the best expected
+//! score. The models are fitted to the same rounds they are scored on, so the
+//! expected totals are what the strategies would have scored with hindsight.
+
+use std::fmt::Display;
+use std::io::BufRead;
+
+use anyhow::{bail, Context, Result};
+use itertools::Itertools;
+
+use super::for_each_round;
+use super::game::Shape;
+use super::rules::Rules;
+
+/// How often the opponent played each shape, by [`Shape`] as an index
+pub type Counts = [u64; 3];
+
+/// The shape with the best expected score against `counts`, and that score
+/// summed over the counted rounds. Ties go to the first in [`Shape::ALL`].
+pub fn best_response(counts: &Counts) -> Result<(Shape, u64)> {
+    let rules = Rules::default();
+    let total_score = |me: Shape| -> Result<u64> {
+        let mut total: u64 = 0;
+        for them in Shape::ALL {
+            total = counts[them as usize]
+                .checked_mul(rules.score_as_shape(them as usize, me as usize))
+                .and_then(|score| total.checked_add(score))
+                .with_context(|| format!("The expected score for {:?} overflows", me))?;
+        }
+
+        Ok(total)
+    };
+
+    let mut best = (Shape::Rock, total_score(Shape::Rock)?);
+    for me in [Shape::Paper, Shape::Scissors] {
+        let total = total_score(me)?;
+        if total > best.1 {
+            best = (me, total);
+        }
+    }
+
+    Ok(best)
+}
+
+/// Counts of the opponent's moves
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OpponentModel {
+    pub frequency: Counts,
+
+    /// By the opponent's previous shape
+    pub transitions: [Counts; 3],
+}
+
+impl OpponentModel {
+    pub fn rounds(&self) -> u64 {
+        self.frequency.iter().sum()
+    }
+
+    /// The frequency model's strategy
+    pub fn frequency_strategy(&self) -> Result<Strategy> {
+        let (shape, total) = best_response(&self.frequency)?;
+
+        Ok(Strategy {
+            responses: vec![(None, shape)],
+            expected: total as f64,
+        })
+    }
+
+    /// The Markov model's strategy. The first round is played like the
+    /// frequency model, and expected to score its average.
+    pub fn markov_strategy(&self) -> Result<Strategy> {
+        let (first, total) = best_response(&self.frequency)?;
+        let mut expected = total as f64 / self.rounds().max(1) as f64;
+
+        let mut responses = vec![(None, first)];
+        for previous in Shape::ALL {
+            let counts = &self.transitions[previous as usize];
+            if counts.iter().all(|count| *count == 0) {
+                continue;
+            }
+
+            let (shape, total) = best_response(counts)?;
+            responses.push((Some(previous), shape));
+            expected += total as f64;
+        }
+
+        Ok(Strategy {
+            responses,
+            expected,
+        })
+    }
+}
+
+/// What to play, after which of the opponent's shapes. `None` stands for the
+/// first round and any shape without a response of its own.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Strategy {
+    pub responses: Vec<(Option<Shape>, Shape)>,
+
+    /// The total expected over the guide's rounds
+    pub expected: f64,
+}
+
+/// `Scissors, Paper after Rock`
+impl Display for Strategy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let responses = self
+            .responses
+            .iter()
+            .map(|(previous, shape)| match previous {
+                None => format!("{:?}", shape),
+                Some(previous) => format!("{:?} after {:?}", shape, previous),
+            });
+
+        write!(f, "{}", responses.format(", "))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Analysis {
+    pub model: OpponentModel,
+    pub frequency: Strategy,
+    pub markov: Strategy,
+
+    /// The guide's own score, read the way part 2 does
+    pub guide_score: u64,
+}
+
+impl Display for Analysis {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let rounds = self.model.rounds();
+        let shares = Shape::ALL.iter().map(|shape| {
+            let count = self.model.frequency[*shape as usize];
+            format!("{:?} {:.1}%", shape, 100.0 * count as f64 / rounds as f64)
+        });
+        writeln!(f, "Opponent: {} rounds, {}", rounds, shares.format(", "))?;
+        writeln!(f, "Guide:     {}", self.guide_score)?;
+
+        for (name, strategy) in [("Frequency", &self.frequency), ("Markov", &self.markov)] {
+            writeln!(
+                f,
+                "{:<10} {:.1} expected ({:+.1}): {}",
+                format!("{}:", name),
+                strategy.expected,
+                strategy.expected - self.guide_score as f64,
+                strategy
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Fits both models to the guide in one pass
+pub fn analyze(reader: impl BufRead) -> Result<Analysis> {
+    let rules = Rules::default();
+    let mut model = OpponentModel::default();
+    let mut guide_score = Some(0u64);
+    let mut previous: Option<Shape> = None;
+
+    for_each_round(reader, |round| {
+        model.frequency[round.them as usize] += 1;
+        if let Some(previous) = previous {
+            model.transitions[previous as usize][round.them as usize] += 1;
+        }
+        previous = Some(round.them);
+        let score = rules.score_as_outcome(round.them as usize, round.column.outcome());
+        guide_score = guide_score.and_then(|total| total.checked_add(score));
+    })?;
+
+    if model.rounds() == 0 {
+        bail!("The guide has no rounds to learn from");
+    }
+    let guide_score = guide_score.context("The guide's score overflows")?;
+
+    Ok(Analysis {
+        frequency: model.frequency_strategy()?,
+        markov: model.markov_strategy()?,
+        model,
+        guide_score,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "A Y
+B X
+C Z";
+
+    #[test]
+    fn test_best_response() {
+        // against rock only, paper wins every round
+        assert_eq!((Shape::Paper, 16), best_response(&[2, 0, 0]).unwrap());
+        // against an even mix the outcomes cancel out and scissors scores most
+        assert_eq!((Shape::Scissors, 18), best_response(&[1, 1, 1]).unwrap());
+        // more rounds than fit in a u32
+        assert_eq!(
+            (Shape::Paper, 8 << 32),
+            best_response(&[1 << 32, 0, 0]).unwrap()
+        );
+        assert!(best_response(&[u64::MAX / 2, 0, 0]).is_err());
+    }
+
+    #[test]
+    fn test_analyze() {
+        let analysis = analyze(EXAMPLE.as_bytes()).unwrap();
+
+        assert_eq!([1, 1, 1], analysis.model.frequency);
+        assert_eq!([0, 1, 0], analysis.model.transitions[Shape::Rock as usize]);
+        assert_eq!(12, analysis.guide_score);
+
+        assert_eq!(vec![(None, Shape::Scissors)], analysis.frequency.responses);
+        assert_eq!(18.0, analysis.frequency.expected);
+
+        // 6 on average in the first round, then scissors beats the paper that
+        // follows rock and rock the scissors that follow paper
+        assert_eq!(
+            vec![
+                (None, Shape::Scissors),
+                (Some(Shape::Rock), Shape::Scissors),
+                (Some(Shape::Paper), Shape::Rock),
+            ],
+            analysis.markov.responses
+        );
+        assert_eq!(22.0, analysis.markov.expected);
+        assert_eq!(
+            "Scissors, Scissors after Rock, Rock after Paper",
+            analysis.markov.to_string()
+        );
+    }
+
+    #[test]
+    fn test_empty_guide() {
+        assert!(analyze("\n".as_bytes()).is_err());
+    }
+}
@@ -0,0 +1,417 @@
+//! Cyclic games with any odd number of shapes, such as
+//! Rock-Paper-Scissors-Lizard-Spock, described in TOML:
+//!
+//! ```toml
+//! # every shape beats the (n - 1) / 2 shapes listed before it, wrapping around
+//! shapes = [
+//!     { name = "Rock", them = "A", me = "X", score = 1 },
+//!     { name = "Paper", them = "B", me = "Y", score = 2 },
+//!     { name = "Scissors", them = "C", me = "Z", score = 3 },
+//! ]
+//!
+//! [outcomes]
+//! lose = { letter = "X", score = 0 }
+//! draw = { letter = "Y", score = 3 }
+//! win = { letter = "Z", score = 6 }
+//! ```
+//!
+//! `them` is the opponent's letter in the first column of the guide. The
+//! second column is either `me`, the shape to play, or an outcome's `letter`.
+//! [`Rules::default`] is the puzzle's game above, which parts 1 and 2 play,
+//! and which the decryption and the opponent models score rounds by. Its
+//! shapes are in [`Shape::ALL`] order, so `shape as usize` is a position in
+//! [`Rules::shapes`].
+
+use std::cmp::Reverse;
+use std::fs;
+use std::io::BufRead;
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+
+use super::game::{Outcome, Shape};
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ShapeRule {
+    pub name: String,
+    pub them: String,
+    pub me: String,
+    pub score: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct OutcomeRule {
+    pub letter: String,
+    pub score: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Outcomes {
+    pub lose: OutcomeRule,
+    pub draw: OutcomeRule,
+    pub win: OutcomeRule,
+}
+
+impl Outcomes {
+    pub fn get(&self, outcome: Outcome) -> &OutcomeRule {
+        match outcome {
+            Outcome::Lose => &self.lose,
+            Outcome::Draw => &self.draw,
+            Outcome::Win => &self.win,
+        }
+    }
+}
+
+/// How to read the second column of the guide
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reading {
+    /// The shape to play, like part 1
+    Shape,
+    /// How the round has to end, like part 2
+    Outcome,
+}
+
+/// Only built by [`Rules::new`], [`Rules::parse`] and [`Rules::default`], so
+/// there is always an odd number of shapes and every outcome can be played for
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Rules {
+    shapes: Vec<ShapeRule>,
+    outcomes: Outcomes,
+}
+
+/// The puzzle's rock, paper, scissors
+impl Default for Rules {
+    fn default() -> Self {
+        let rule = |outcome: Outcome, letter: &str| OutcomeRule {
+            letter: letter.to_string(),
+            score: outcome.score(),
+        };
+
+        Rules {
+            shapes: Shape::ALL
+                .iter()
+                .zip(["A", "B", "C"].iter().zip(["X", "Y", "Z"]))
+                .map(|(shape, (them, me))| ShapeRule {
+                    name: format!("{:?}", shape),
+                    them: them.to_string(),
+                    me: me.to_string(),
+                    score: shape.score(),
+                })
+                .collect(),
+            outcomes: Outcomes {
+                lose: rule(Outcome::Lose, "X"),
+                draw: rule(Outcome::Draw, "Y"),
+                win: rule(Outcome::Win, "Z"),
+            },
+        }
+    }
+}
+
+impl Rules {
+    pub fn new(shapes: Vec<ShapeRule>, outcomes: Outcomes) -> Result<Self> {
+        let rules = Rules { shapes, outcomes };
+        rules.validate()?;
+
+        Ok(rules)
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = fs::read_to_string(path)
+            .with_context(|| format!("Could not read {}", path.display()))?;
+        Rules::parse(&text).with_context(|| format!("Could not parse {}", path.display()))
+    }
+
+    pub fn parse(text: &str) -> Result<Self> {
+        let rules: Rules = toml::from_str(text)?;
+        rules.validate()?;
+
+        Ok(rules)
+    }
+
+    /// In cyclic order, see the module docs
+    pub fn shapes(&self) -> &[ShapeRule] {
+        &self.shapes
+    }
+
+    pub fn outcomes(&self) -> &Outcomes {
+        &self.outcomes
+    }
+
+    fn validate(&self) -> Result<()> {
+        let n = self.shapes.len();
+        if n < 3 || n.is_multiple_of(2) {
+            bail!("Expected an odd number of shapes, at least 3, not {}", n);
+        }
+
+        let outcome_letters = Outcome::ALL.map(|o| self.outcomes.get(o).letter.as_str());
+        let columns: [(&str, Vec<&str>); 3] = [
+            (
+                "them",
+                self.shapes.iter().map(|s| s.them.as_str()).collect(),
+            ),
+            ("me", self.shapes.iter().map(|s| s.me.as_str()).collect()),
+            ("outcome", outcome_letters.to_vec()),
+        ];
+        for (column, letters) in columns {
+            for (idx, letter) in letters.iter().enumerate() {
+                if letter.is_empty() || letter.contains(char::is_whitespace) {
+                    bail!("Invalid {} letter {:?}", column, letter);
+                }
+                if letters[..idx].contains(letter) {
+                    bail!("The {} letter {:?} is used twice", column, letter);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The outcome of playing shape `me` against shape `them`, by their
+    /// positions in [`Rules::shapes`]
+    pub fn outcome(&self, me: usize, them: usize) -> Outcome {
+        let n = self.shapes.len();
+        match (me + n - them) % n {
+            0 => Outcome::Draw,
+            d if d <= n / 2 => Outcome::Win,
+            _ => Outcome::Lose,
+        }
+    }
+
+    /// The shape to play against `them` for `outcome`. With more than three
+    /// shapes several can do, and the one that scores most is picked.
+    pub fn for_outcome(&self, them: usize, outcome: Outcome) -> usize {
+        // a draw is `them` itself, and a win or a loss the half of the other
+        // shapes after or before it in the cycle, which is never empty
+        let n = self.shapes.len();
+        let (first, len) = match outcome {
+            Outcome::Draw => (them, 1),
+            Outcome::Win => (them + 1, n / 2),
+            Outcome::Lose => (them + n / 2 + 1, n / 2),
+        };
+        let key = |me: usize| (self.shapes[me].score, Reverse(me));
+
+        let mut best = first % n;
+        for me in (first + 1..first + len).map(|me| me % n) {
+            if key(me) > key(best) {
+                best = me;
+            }
+        }
+
+        best
+    }
+
+    /// In `u64`, so any two scores from the rules add up without overflowing
+    pub fn score(&self, me: usize, outcome: Outcome) -> u64 {
+        u64::from(self.shapes[me].score) + u64::from(self.outcomes.get(outcome).score)
+    }
+
+    /// The score of playing shape `me` against shape `them`
+    pub fn score_as_shape(&self, them: usize, me: usize) -> u64 {
+        self.score(me, self.outcome(me, them))
+    }
+
+    /// The score of playing against shape `them` for `outcome`
+    pub fn score_as_outcome(&self, them: usize, outcome: Outcome) -> u64 {
+        self.score(self.for_outcome(them, outcome), outcome)
+    }
+
+    /// The score of one line of the guide, `A Y` in the puzzle's game
+    pub fn score_line(&self, line: &str, reading: Reading) -> Result<u64> {
+        let mut iter = line.split_whitespace();
+        let (Some(them), Some(column), None) = (iter.next(), iter.next(), iter.next()) else {
+            bail!("Expected two columns in {:?}", line);
+        };
+
+        let them = self
+            .shapes
+            .iter()
+            .position(|s| s.them == them)
+            .with_context(|| format!("Invalid shape {:?}", them))?;
+
+        Ok(match reading {
+            Reading::Shape => {
+                let me = self
+                    .shapes
+                    .iter()
+                    .position(|s| s.me == column)
+                    .with_context(|| format!("Invalid shape {:?}", column))?;
+                self.score_as_shape(them, me)
+            }
+            Reading::Outcome => {
+                let outcome = Outcome::ALL
+                    .into_iter()
+                    .find(|o| self.outcomes.get(*o).letter == column)
+                    .with_context(|| format!("Invalid outcome {:?}", column))?;
+                self.score_as_outcome(them, outcome)
+            }
+        })
+    }
+
+    /// The total score of the guide, skipping blank lines
+    pub fn total(&self, reader: impl BufRead, reading: Reading) -> Result<u64> {
+        let mut total: u64 = 0;
+        for (line_no, line) in reader.lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let score = self
+                .score_line(&line, reading)
+                .with_context(|| format!("Invalid line {}", line_no))?;
+            total = total
+                .checked_add(score)
+                .context("The total score overflows")?;
+        }
+
+        Ok(total)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RPSLS: &str = r#"
+        shapes = [
+            { name = "Rock", them = "A", me = "V", score = 1 },
+            { name = "Spock", them = "B", me = "W", score = 2 },
+            { name = "Paper", them = "C", me = "X", score = 3 },
+            { name = "Lizard", them = "D", me = "Y", score = 4 },
+            { name = "Scissors", them = "E", me = "Z", score = 5 },
+        ]
+
+        [outcomes]
+        lose = { letter = "L", score = 0 }
+        draw = { letter = "D", score = 3 }
+        win = { letter = "W", score = 6 }
+    "#;
+
+    #[test]
+    fn test_default_matches_the_puzzle() {
+        let rules = Rules::default();
+        let shape = |shape: Shape| shape as usize;
+
+        assert_eq!(
+            Outcome::Win,
+            rules.outcome(shape(Shape::Paper), shape(Shape::Rock))
+        );
+        assert_eq!(
+            Outcome::Win,
+            rules.outcome(shape(Shape::Rock), shape(Shape::Scissors))
+        );
+        assert_eq!(
+            Outcome::Lose,
+            rules.outcome(shape(Shape::Rock), shape(Shape::Paper))
+        );
+        for them in 0..3 {
+            assert_eq!(Outcome::Draw, rules.outcome(them, them));
+            for outcome in Outcome::ALL {
+                assert_eq!(
+                    outcome,
+                    rules.outcome(rules.for_outcome(them, outcome), them)
+                );
+            }
+        }
+
+        // the example's rounds, read both ways
+        for (line, as_shape, as_outcome) in [("A Y", 8, 4), ("B X", 1, 1), ("C Z", 6, 7)] {
+            assert_eq!(as_shape, rules.score_line(line, Reading::Shape).unwrap());
+            assert_eq!(
+                as_outcome,
+                rules.score_line(line, Reading::Outcome).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn test_new() {
+        let rules = Rules::default();
+
+        assert_eq!(
+            rules,
+            Rules::new(rules.shapes().to_vec(), rules.outcomes().clone()).unwrap()
+        );
+        assert!(Rules::new(Vec::new(), rules.outcomes().clone()).is_err());
+        assert!(Rules::new(rules.shapes()[..2].to_vec(), rules.outcomes().clone()).is_err());
+    }
+
+    #[test]
+    fn test_lizard_spock() {
+        let rules = Rules::parse(RPSLS).unwrap();
+        let idx = |name: &str| rules.shapes.iter().position(|s| s.name == name).unwrap();
+
+        let wins = [
+            ("Scissors", "Paper"),
+            ("Paper", "Rock"),
+            ("Rock", "Lizard"),
+            ("Lizard", "Spock"),
+            ("Spock", "Scissors"),
+            ("Scissors", "Lizard"),
+            ("Lizard", "Paper"),
+            ("Paper", "Spock"),
+            ("Spock", "Rock"),
+            ("Rock", "Scissors"),
+        ];
+        for (winner, loser) in wins {
+            assert_eq!(Outcome::Win, rules.outcome(idx(winner), idx(loser)));
+            assert_eq!(Outcome::Lose, rules.outcome(idx(loser), idx(winner)));
+        }
+
+        // lizard (4) + win against spock
+        assert_eq!(10, rules.score_line("B Y", Reading::Shape).unwrap());
+        // scissors (5) and lizard (4) both beat paper
+        assert_eq!(11, rules.score_line("C W", Reading::Outcome).unwrap());
+        assert_eq!(
+            1 + 3 + 10,
+            rules
+                .total("A V\n\nB Y\n".as_bytes(), Reading::Shape)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_large_scores() {
+        let rules = Rules::parse(
+            &RPSLS
+                .replace("score = 5 }", "score = 4294967295 }")
+                .replace("score = 6 }", "score = 4294967295 }"),
+        )
+        .unwrap();
+
+        // scissors against paper
+        assert_eq!(
+            2 * 4294967295,
+            rules.score_line("C Z", Reading::Shape).unwrap()
+        );
+        assert_eq!(
+            4 * 4294967295,
+            rules
+                .total("C Z\nC Z\n".as_bytes(), Reading::Shape)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let four_shapes = RPSLS.replace(
+            r#"{ name = "Scissors", them = "E", me = "Z", score = 5 },"#,
+            "",
+        );
+        assert!(Rules::parse(&four_shapes).is_err());
+        assert!(Rules::parse(&RPSLS.replace(r#"them = "E""#, r#"them = "A""#)).is_err());
+        assert!(Rules::parse(&RPSLS.replace(r#"letter = "W""#, r#"letter = "L""#)).is_err());
+        assert!(Rules::parse(&RPSLS.replace(r#"me = "Z""#, r#"me = """#)).is_err());
+        assert!(Rules::parse("shapes = []").is_err());
+
+        let rules = Rules::parse(RPSLS).unwrap();
+        assert!(rules.score_line("F V", Reading::Shape).is_err());
+        assert!(rules.score_line("A L", Reading::Shape).is_err());
+        assert!(rules.score_line("A V", Reading::Outcome).is_err());
+    }
+}
@@ -1,116 +1,429 @@
 use anyhow::{bail, Context, Result};
 use itertools::Itertools;
-use std::collections::HashSet;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::fmt::Display;
+use std::io::BufRead;
+use std::ops::BitAnd;
 
-pub fn run(part: u8) -> Result<()> {
+use crate::params;
+
+pub fn solve(part: u8, lines: &str) -> Result<String> {
+    solve_reader(part, lines.as_bytes())
+}
+
+/// Like [`solve`], reading the input a line at a time
+pub fn solve_reader(part: u8, reader: impl BufRead) -> Result<String> {
     if part == 1 {
-        part1()
+        Ok(part1(reader)?.to_string())
     } else {
-        part2()
+        Ok(part2(reader)?.to_string())
     }
 }
 
-fn part2() -> Result<()> {
-    println!("Running day3::part2");
+/// An item, one of the letters `a` to `z` and `A` to `Z`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Item(char);
+
+impl Item {
+    /// 1 to 26 for `a` to `z`, 27 to 52 for `A` to `Z`
+    pub fn priority(self) -> u16 {
+        if self.0.is_ascii_uppercase() {
+            self.0 as u16 - 'A' as u16 + 27
+        } else {
+            self.0 as u16 - 'a' as u16 + 1
+        }
+    }
 
-    let file = File::open("./resources/day3.txt").context("Could not find day3.txt")?;
-    let reader = BufReader::new(file);
+    pub fn from_priority(priority: u16) -> Option<Item> {
+        match priority {
+            1..=26 => Some(Item((b'a' + (priority - 1) as u8) as char)),
+            27..=52 => Some(Item((b'A' + (priority - 27) as u8) as char)),
+            _ => None,
+        }
+    }
+}
 
-    let total: u16 = reader
-        .lines()
-        .into_iter()
-        .batching(|it| Some((it.next()?, it.next()?, it.next()?)))
-        .map(|g| find_badge_in_group(&g.0.unwrap(), &g.1.unwrap(), &g.2.unwrap()))
-        .map(|c| item_priority(c.unwrap()))
-        .sum();
+impl TryFrom<char> for Item {
+    type Error = anyhow::Error;
 
-    println!("Sum of priorities = {}", total);
-    Ok(())
+    fn try_from(ch: char) -> Result<Self> {
+        if !ch.is_ascii_alphabetic() {
+            bail!("Invalid item {:?}, expected a letter", ch);
+        }
+
+        Ok(Item(ch))
+    }
 }
 
-fn part1() -> Result<()> {
-    println!("Running day3::part1");
+impl Display for Item {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
 
-    let file = File::open("./resources/day3.txt").context("Could not find day3.txt")?;
-    let reader = BufReader::new(file);
+/// A set of items, with bit `n` set for the item with priority `n`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ItemSet(u64);
 
-    let mut total = 0;
-    for (_index, line) in reader.lines().enumerate() {
-        let line = line?;
+impl ItemSet {
+    /// The set of items in all of `sets`, empty when there are none
+    pub fn common(sets: impl IntoIterator<Item = ItemSet>) -> ItemSet {
+        sets.into_iter().reduce(BitAnd::bitand).unwrap_or_default()
+    }
 
-        let half = line.len() / 2;
-        let (first, second) = line.split_at(half);
-        let dup = find_dup(first, second)?;
+    pub fn insert(&mut self, item: Item) {
+        self.0 |= 1 << item.priority();
+    }
 
-        total += item_priority(dup);
+    pub fn len(self) -> usize {
+        self.0.count_ones() as usize
     }
 
-    println!("Sum of priorities = {}", total);
-    Ok(())
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// The items, lowest priority first
+    pub fn items(self) -> impl Iterator<Item = Item> {
+        (1..=52)
+            .filter(move |p| self.0 & 1 << p != 0)
+            .filter_map(Item::from_priority)
+    }
+
+    /// The only item in the set, or an error naming what was found instead
+    pub fn single(self, what: &str) -> Result<Item> {
+        match self.len() {
+            1 => Ok(self.items().next().unwrap()),
+            0 => bail!("No {} found", what),
+            n => bail!(
+                "Expected one {}, found {}: {}",
+                what,
+                n,
+                self.items().join(", ")
+            ),
+        }
+    }
 }
 
-fn find_badge_in_group(s1: &str, s2: &str, s3: &str) -> Result<char> {
-    let set1: HashSet<char> = s1.chars().collect();
-    let set2: HashSet<char> = s2.chars().collect();
+impl FromIterator<Item> for ItemSet {
+    fn from_iter<T: IntoIterator<Item = Item>>(iter: T) -> Self {
+        let mut set = ItemSet::default();
+        for item in iter {
+            set.insert(item);
+        }
+        set
+    }
+}
 
-    let dups1_2: String = set1.intersection(&set2).collect();
-    //println!("dups between {} and {} = {}", s1, s2, dups1_2);
+impl BitAnd for ItemSet {
+    type Output = ItemSet;
 
-    let dup = find_dup(&dups1_2, s3)?;
+    fn bitand(self, rhs: ItemSet) -> ItemSet {
+        ItemSet(self.0 & rhs.0)
+    }
+}
 
-    Ok(dup)
+/// What is wrong with a rucksack's line
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Problem {
+    /// A character that is not an item, at a column counting from 1
+    InvalidItem {
+        ch: char,
+        column: usize,
+    },
+    /// An odd number of items, which cannot split into two compartments
+    OddLength(usize),
+    NoneShared,
+    SeveralShared(ItemSet),
+}
+
+impl Display for Problem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Problem::InvalidItem { ch, column } => {
+                write!(f, "Invalid item {:?} at column {}", ch, column)
+            }
+            Problem::OddLength(len) => write!(f, "Odd number of items ({})", len),
+            Problem::NoneShared => write!(f, "No item in both compartments"),
+            Problem::SeveralShared(items) => write!(
+                f,
+                "{} items in both compartments: {}",
+                items.len(),
+                items.items().join(", ")
+            ),
+        }
+    }
 }
 
-fn find_dup(s1: &str, s2: &str) -> Result<char> {
-    let set1: HashSet<char> = s1.chars().collect();
+impl std::error::Error for Problem {}
 
-    for ch in s2.chars() {
-        if set1.contains(&ch) {
-            return Ok(ch);
+/// A rucksack's two compartments, each half of the line
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rucksack {
+    pub first: ItemSet,
+    pub second: ItemSet,
+}
+
+impl Rucksack {
+    pub fn new(line: &str) -> Result<Rucksack, Problem> {
+        let items: Vec<Item> = line
+            .chars()
+            .enumerate()
+            .map(|(idx, ch)| {
+                Item::try_from(ch).map_err(|_| Problem::InvalidItem {
+                    ch,
+                    column: idx + 1,
+                })
+            })
+            .try_collect()?;
+        if items.len() % 2 == 1 {
+            return Err(Problem::OddLength(items.len()));
         }
+
+        let (first, second) = items.split_at(items.len() / 2);
+        Ok(Rucksack {
+            first: first.iter().copied().collect(),
+            second: second.iter().copied().collect(),
+        })
+    }
+
+    /// Everything in the rucksack
+    pub fn items(&self) -> ItemSet {
+        ItemSet(self.first.0 | self.second.0)
+    }
+
+    /// The items packed in both compartments
+    pub fn shared(&self) -> ItemSet {
+        self.first & self.second
     }
 
-    bail!("Duplicate not found in strings {} and {}", s1, s2);
+    /// The one item packed in both compartments, as the puzzle promises
+    pub fn shared_item(&self) -> Result<Item, Problem> {
+        let shared = self.shared();
+        match shared.len() {
+            0 => Err(Problem::NoneShared),
+            1 => Ok(shared.items().next().unwrap()),
+            _ => Err(Problem::SeveralShared(shared)),
+        }
+    }
 }
 
-fn item_priority(ch: char) -> u16 {
-    if ch.is_uppercase() {
-        let p = ch as u16 - 'A' as u16;
-        p + 27
-    } else {
-        let p = ch as u16 - 'a' as u16;
-        p + 1
+/// The items every rucksack in the group carries, the candidates for its badge
+pub fn group_badges(group: &[Rucksack]) -> ItemSet {
+    ItemSet::common(group.iter().map(Rucksack::items))
+}
+
+/// Every malformed rucksack, by line number counting from 1, instead of
+/// stopping at the first like the parts do
+pub fn diagnose(reader: impl BufRead) -> Result<Vec<(usize, Problem)>> {
+    let mut problems = Vec::new();
+    for (line_no, line) in reader.lines().enumerate() {
+        let checked = Rucksack::new(&line?).and_then(|rucksack| rucksack.shared_item());
+        if let Err(problem) = checked {
+            problems.push((line_no + 1, problem));
+        }
+    }
+
+    Ok(problems)
+}
+
+fn part2(reader: impl BufRead) -> Result<u64> {
+    println!("Running day3::part2");
+
+    let group_size = params::get("group", 3);
+    let size = usize::try_from(group_size)
+        .ok()
+        .filter(|size| *size >= 1)
+        .with_context(|| format!("Invalid group size {}", group_size))?;
+
+    let mut total: u64 = 0;
+    let mut group = Vec::new();
+    for (line_no, line) in reader.lines().enumerate() {
+        let rucksack =
+            Rucksack::new(&line?).with_context(|| format!("Invalid line {}", line_no + 1))?;
+        group.push(rucksack);
+
+        if group.len() == size {
+            let badge = group_badges(&group)
+                .single("badge")
+                .with_context(|| format!("Invalid group ending on line {}", line_no + 1))?;
+            total = total
+                .checked_add(badge.priority().into())
+                .context("The sum of priorities overflows")?;
+            group.clear();
+        }
+    }
+    if !group.is_empty() {
+        bail!("The last group has {} of {} rucksacks", group.len(), size);
+    }
+
+    println!("Sum of priorities = {}", total);
+    Ok(total)
+}
+
+fn part1(reader: impl BufRead) -> Result<u64> {
+    println!("Running day3::part1");
+
+    let mut total: u64 = 0;
+    for (line_no, line) in reader.lines().enumerate() {
+        let item = Rucksack::new(&line?)
+            .and_then(|rucksack| rucksack.shared_item())
+            .with_context(|| format!("Invalid line {}", line_no + 1))?;
+        total = total
+            .checked_add(item.priority().into())
+            .context("The sum of priorities overflows")?;
     }
+
+    println!("Sum of priorities = {}", total);
+    Ok(total)
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::day03::{find_badge_in_group, find_dup, item_priority};
+    use super::*;
+
+    const EXAMPLE: &str = "vJrwpWtwJgWrhcsFMMfFFhFp
+jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL
+PmmdzqPrVvPwwTWBwg
+wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn
+ttgJtRGJQctTZtZT
+CrZsJsPPZsGzwwsLwLmpwMDw";
+
+    fn items(set: ItemSet) -> String {
+        set.items().join("")
+    }
 
     #[test]
     fn test_item_priority() {
-        assert_eq!(1, item_priority('a'));
-        assert_eq!(26, item_priority('z'));
-        assert_eq!(27, item_priority('A'));
-        assert_eq!(52, item_priority('Z'));
+        let priority = |ch: char| Item::try_from(ch).unwrap().priority();
+
+        assert_eq!(1, priority('a'));
+        assert_eq!(26, priority('z'));
+        assert_eq!(27, priority('A'));
+        assert_eq!(52, priority('Z'));
+
+        for priority in 1..=52 {
+            assert_eq!(priority, Item::from_priority(priority).unwrap().priority());
+        }
+        assert_eq!(None, Item::from_priority(0));
+        assert_eq!(None, Item::from_priority(53));
     }
 
     #[test]
-    fn test_find_dup() {
-        assert_eq!('a', find_dup("abcdefg", "xyzarty").unwrap());
+    fn test_invalid_item() {
+        for ch in ['0', ' ', '-', 'é', '{', '@'] {
+            assert!(Item::try_from(ch).is_err());
+        }
     }
 
     #[test]
-    fn test_find_badge_in_group() {
-        let (s1, s2, s3) = (
-            "vJrwpWtwJgWrhcsFMMfFFhFp",
-            "jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL",
-            "PmmdzqPrVvPwwTWBwg",
+    fn test_shared() {
+        assert_eq!(
+            "a",
+            items(Rucksack::new("abcdefgxyzarty").unwrap().shared())
         );
+        assert_eq!("ac", items(Rucksack::new("abcxca").unwrap().shared()));
+    }
+
+    #[test]
+    fn test_group_badges() {
+        let group: Vec<Rucksack> = EXAMPLE
+            .lines()
+            .take(3)
+            .map(|line| Rucksack::new(line).unwrap())
+            .collect();
+
+        assert_eq!("r", items(group_badges(&group)));
+        assert!(group_badges(&[]).is_empty());
+    }
 
-        let result = find_badge_in_group(s1, s2, s3).unwrap();
-        assert_eq!('r', result);
+    #[test]
+    fn test_single() {
+        let set = |s: &str| {
+            s.chars()
+                .map(|ch| Item::try_from(ch).unwrap())
+                .collect::<ItemSet>()
+        };
+
+        assert_eq!(Item('a'), set("aaa").single("badge").unwrap());
+        assert_eq!(
+            "Expected one badge, found 2: a, B",
+            set("Ba").single("badge").unwrap_err().to_string()
+        );
+        assert!(ItemSet::default().single("badge").is_err());
+    }
+
+    #[test]
+    fn test_diagnose() {
+        let input = "abca\nabc\nab1a\nabcd\nabab\n";
+
+        assert_eq!(
+            vec![
+                (2, Problem::OddLength(3)),
+                (3, Problem::InvalidItem { ch: '1', column: 3 }),
+                (4, Problem::NoneShared),
+                (
+                    5,
+                    Problem::SeveralShared(Rucksack::new("ab").unwrap().items())
+                ),
+            ],
+            diagnose(input.as_bytes()).unwrap()
+        );
+        assert!(diagnose(EXAMPLE.as_bytes()).unwrap().is_empty());
+        assert_eq!(
+            "2 items in both compartments: a, b",
+            Problem::SeveralShared(Rucksack::new("ab").unwrap().items()).to_string()
+        );
+    }
+
+    #[test]
+    fn test_part1_example() {
+        assert_eq!(157, part1(EXAMPLE.as_bytes()).unwrap());
+    }
+
+    #[test]
+    fn test_part1_invalid() {
+        let err = part1("abca\nab1a\n".as_bytes()).unwrap_err();
+
+        assert_eq!("Invalid line 2", err.to_string());
+        assert_eq!("Invalid item '1' at column 3", err.root_cause().to_string());
+    }
+
+    #[test]
+    fn test_part2_invalid() {
+        let err = part2("abca\nabcb\nab1a\n".as_bytes()).unwrap_err();
+
+        assert_eq!("Invalid line 3", err.to_string());
+    }
+
+    #[test]
+    fn test_part2_example() {
+        assert_eq!(70, part2(EXAMPLE.as_bytes()).unwrap());
+    }
+
+    #[test]
+    fn test_part2_group_size() {
+        let params = params::Params::from([("group".to_string(), 2)]);
+        let input = "abcd\nxbyz\nbEFq\nGHqZ";
+
+        // b then q
+        assert_eq!(
+            2 + 17,
+            params::run_with(&params, || part2(input.as_bytes())).unwrap()
+        );
+        // in groups of three the last line is left over
+        assert_eq!(
+            "The last group has 1 of 3 rucksacks",
+            part2(input.as_bytes()).unwrap_err().to_string()
+        );
+
+        let params = params::Params::from([("group".to_string(), 1 << 40)]);
+        assert_eq!(
+            "The last group has 4 of 1099511627776 rucksacks",
+            params::run_with(&params, || part2(input.as_bytes()))
+                .unwrap_err()
+                .to_string()
+        );
+        let params = params::Params::from([("group".to_string(), 0)]);
+        assert!(params::run_with(&params, || part2(input.as_bytes())).is_err());
     }
 }
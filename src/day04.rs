@@ -1,52 +1,61 @@
 use anyhow::{Context, Result};
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::io::BufRead;
+
 use std::ops::RangeInclusive;
 
-pub fn run(part: u8) -> Result<()> {
+pub fn solve(part: u8, lines: &str) -> Result<String> {
+    solve_reader(part, lines.as_bytes())
+}
+
+/// Like [`solve`], reading the input a line at a time
+pub fn solve_reader(part: u8, reader: impl BufRead) -> Result<String> {
     if part == 1 {
-        part1()
+        Ok(part1(reader)?.to_string())
     } else {
-        part2()
+        Ok(part2(reader)?.to_string())
     }
 }
 
-fn part2() -> Result<()> {
+fn part2(reader: impl BufRead) -> Result<usize> {
     println!("Running day4::part2");
 
-    let file = File::open("./resources/day4.txt").context("Could not find day4.txt")?;
-    let reader = BufReader::new(file);
-
-    let total = reader
-        .lines()
-        .into_iter()
-        .map(|line| parse_line(&line.unwrap()).unwrap())
-        .filter(|(a1, a2)| overlap(a1, a2))
-        .count();
+    let mut total = 0;
+    for (line_no, line) in reader.lines().enumerate() {
+        let (a1, a2) = line
+            .with_context(|| format!("Could not read line {}", line_no))
+            .and_then(|line| parse_line(&line))
+            .with_context(|| format!("Invalid line {}", line_no))?;
+        if overlap(&a1, &a2) {
+            total += 1;
+        }
+    }
 
     println!("Number of pairs that overlap the other: {}", total);
-    Ok(())
+    Ok(total)
 }
 
-fn part1() -> Result<()> {
+fn part1(reader: impl BufRead) -> Result<usize> {
     println!("Running day4::part1");
 
-    let file = File::open("./resources/day4.txt").context("Could not find day4.txt")?;
-    let reader = BufReader::new(file);
-
-    let total = reader
-        .lines()
-        .into_iter()
-        .map(|line| parse_line(&line.unwrap()).unwrap())
-        .filter(|(a1, a2)| contains(a1, a2))
-        .count();
+    let mut total = 0;
+    for (line_no, line) in reader.lines().enumerate() {
+        let (a1, a2) = line
+            .with_context(|| format!("Could not read line {}", line_no))
+            .and_then(|line| parse_line(&line))
+            .with_context(|| format!("Invalid line {}", line_no))?;
+        if contains(&a1, &a2) {
+            total += 1;
+        }
+    }
 
     println!("Number of pairs that contain the other: {}", total);
-    Ok(())
+    Ok(total)
 }
 
-fn parse_line(line: &str) -> Result<(RangeInclusive<u32>, RangeInclusive<u32>)> {
-    let (a1, a2) = line.split_once(',').unwrap();
+pub fn parse_line(line: &str) -> Result<(RangeInclusive<u32>, RangeInclusive<u32>)> {
+    let (a1, a2) = line
+        .split_once(',')
+        .with_context(|| format!("Expected a ',' between the assignments in {}", line))?;
     let r1 = parse_range(a1)?;
     let r2 = parse_range(a2)?;
 
@@ -54,7 +63,9 @@ fn parse_line(line: &str) -> Result<(RangeInclusive<u32>, RangeInclusive<u32>)>
 }
 
 fn parse_range(range_str: &str) -> Result<RangeInclusive<u32>> {
-    let (start_str, end_str) = range_str.split_once('-').unwrap();
+    let (start_str, end_str) = range_str
+        .split_once('-')
+        .with_context(|| format!("Expected a '-' in the range {}", range_str))?;
 
     let start: u32 = start_str.parse()?;
     let end: u32 = end_str.parse()?;
@@ -125,4 +136,19 @@ mod tests {
         assert_eq!(&6, r2.start());
         assert_eq!(&8, r2.end());
     }
+
+    #[test]
+    fn test_parse_line_malformed() {
+        assert!(parse_line("1-5").is_err());
+        assert!(parse_line("1-5,6").is_err());
+        assert!(parse_line("a-5,6-8").is_err());
+    }
+
+    #[test]
+    fn test_part1_malformed() {
+        let err = part1("2-4,6-8\n2-4,6-x\n".as_bytes()).unwrap_err();
+
+        assert_eq!("Invalid line 1", err.to_string());
+        assert!(part2("2-4\n".as_bytes()).is_err());
+    }
 }
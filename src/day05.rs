@@ -1,17 +1,17 @@
-use anyhow::{Context, Result};
+use std::fmt::Display;
+
+use anyhow::{bail, Context, Result};
 use regex::Regex;
-use std::fs::File;
-use std::io::Read;
 
-pub fn run(part: u8) -> Result<()> {
+pub fn solve(part: u8, lines: &str) -> Result<String> {
     if part == 1 {
-        part1()
+        Ok(part1(lines)?.to_string())
     } else {
-        part2()
+        Ok(part2(lines)?.to_string())
     }
 }
 
-struct CrateStacks {
+pub struct CrateStacks {
     stacks: Vec<Vec<char>>,
 }
 
@@ -31,12 +31,46 @@ impl CrateStacks {
         }
     }
 
-    fn push(&mut self, idx: usize, val: char) {
-        if idx > self.stacks.len() {
-            unimplemented!("Currently hard coding the number of stacks and this push goes beyond the pre-allocated size");
+    fn push(&mut self, idx: usize, val: char) -> Result<()> {
+        let count = self.stacks.len();
+        let stack = self
+            .stacks
+            .get_mut(idx)
+            .with_context(|| format!("Crate {} is outside the {} stacks", val, count))?;
+        stack.push(val);
+
+        Ok(())
+    }
+
+    /// The positions of the stacks numbered `from_stack` and `to_stack`,
+    /// checking that `from_stack` has `count` crates to move
+    fn check_move(
+        &self,
+        count: usize,
+        from_stack: usize,
+        to_stack: usize,
+    ) -> Result<(usize, usize)> {
+        for stack in [from_stack, to_stack] {
+            if !(1..=self.stacks.len()).contains(&stack) {
+                bail!(
+                    "There is no stack {}, only 1 to {}",
+                    stack,
+                    self.stacks.len()
+                );
+            }
+        }
+
+        let available = self.stacks[from_stack - 1].len();
+        if count > available {
+            bail!(
+                "Stack {} has {} crates, not {}",
+                from_stack,
+                available,
+                count
+            );
         }
 
-        self.stacks.get_mut(idx).unwrap().push(val);
+        Ok((from_stack - 1, to_stack - 1))
     }
 
     fn get_top_as_string(self) -> String {
@@ -57,11 +91,14 @@ impl CrateStacks {
         count: usize,
         from_stack: usize,
         to_stack: usize,
-    ) {
+    ) -> Result<()> {
+        let (from, to) = self.check_move(count, from_stack, to_stack)?;
         for _ in 0..count {
-            let item = self.stacks[from_stack - 1].pop().unwrap();
-            self.stacks[to_stack - 1].push(item);
+            let item = self.stacks[from].pop().unwrap();
+            self.stacks[to].push(item);
         }
+
+        Ok(())
     }
 
     fn move_crates_with_crate_mover_9001(
@@ -69,67 +106,80 @@ impl CrateStacks {
         count: usize,
         from_stack: usize,
         to_stack: usize,
-    ) {
-        let mut tmp = Vec::new();
-        for _ in 0..count {
-            let item = self.stacks[from_stack - 1].pop().unwrap();
-            tmp.push(item);
-        }
-        tmp.reverse();
-        for item in tmp {
-            self.stacks[to_stack - 1].push(item);
-        }
+    ) -> Result<()> {
+        let (from, to) = self.check_move(count, from_stack, to_stack)?;
+        let start = self.stacks[from].len() - count;
+        let moved: Vec<char> = self.stacks[from].drain(start..).collect();
+        self.stacks[to].extend(moved);
+
+        Ok(())
     }
 }
 
-struct MoveInstruction {
+pub struct MoveInstruction {
     count: usize,
     from_stack: usize,
     to_stack: usize,
 }
 
-fn part2() -> Result<()> {
+/// `move 1 from 2 to 1`, as in the input
+impl Display for MoveInstruction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "move {} from {} to {}",
+            self.count, self.from_stack, self.to_stack
+        )
+    }
+}
+
+fn part2(lines: &str) -> Result<String> {
     println!("Running day5::part2");
-    let (mut stacks, mut instructions) = read_file()?;
+    let (mut stacks, mut instructions) = parse_input(lines)?;
 
     for instruction in instructions.iter_mut() {
-        stacks.move_crates_with_crate_mover_9001(
-            instruction.count,
-            instruction.from_stack,
-            instruction.to_stack,
-        );
+        stacks
+            .move_crates_with_crate_mover_9001(
+                instruction.count,
+                instruction.from_stack,
+                instruction.to_stack,
+            )
+            .with_context(|| format!("Could not {}", instruction))?;
     }
 
-    println!("The top of the stack: {}", stacks.get_top_as_string());
+    let top = stacks.get_top_as_string();
+    println!("The top of the stack: {}", top);
 
-    Ok(())
+    Ok(top)
 }
 
-fn part1() -> Result<()> {
+fn part1(lines: &str) -> Result<String> {
     println!("Running day5::part1");
 
-    let (mut stacks, mut instructions) = read_file()?;
+    let (mut stacks, mut instructions) = parse_input(lines)?;
 
     for instruction in instructions.iter_mut() {
-        stacks.move_crates_with_crate_mover_9000(
-            instruction.count,
-            instruction.from_stack,
-            instruction.to_stack,
-        );
+        stacks
+            .move_crates_with_crate_mover_9000(
+                instruction.count,
+                instruction.from_stack,
+                instruction.to_stack,
+            )
+            .with_context(|| format!("Could not {}", instruction))?;
     }
 
-    println!("The top of the stack: {}", stacks.get_top_as_string());
+    let top = stacks.get_top_as_string();
+    println!("The top of the stack: {}", top);
 
-    Ok(())
+    Ok(top)
 }
 
-// Reads the file and returns the CrateStacks along with a list of move instructions
-fn read_file() -> Result<(CrateStacks, Vec<MoveInstruction>)> {
-    let mut file = File::open("./resources/day5.txt").context("Could not find day5.txt")?;
-    let mut lines = String::new();
-    file.read_to_string(&mut lines)?;
-
-    let (stack_strs, instruction_strs) = lines.split_once("\n\n").unwrap();
+/// Splits the input on the blank line and parses the stacks above it and the
+/// move instructions below it
+pub fn parse_input(lines: &str) -> Result<(CrateStacks, Vec<MoveInstruction>)> {
+    let (stack_strs, instruction_strs) = lines
+        .split_once("\n\n")
+        .context("Expected a blank line between the stacks and the instructions")?;
 
     let stacks = parse_stacks(stack_strs)?;
     let inst = parse_instructions(instruction_strs)?;
@@ -137,24 +187,29 @@ fn read_file() -> Result<(CrateStacks, Vec<MoveInstruction>)> {
     Ok((stacks, inst))
 }
 
-fn parse_stacks(stack_strs: &str) -> Result<CrateStacks> {
-    // Hard coding the number of stacks as a shortcut
-    let mut stacks = CrateStacks::new(9);
-
-    for line in stack_strs.split('\n') {
-        let mut pos = 1; // position in line
-        let mut idx = 0; // zero based index of stack
-        while pos < line.len() {
-            let val = line
-                .chars()
-                .nth(pos)
-                .with_context(|| format!("Could not get {}th character from {}", pos, line))
-                .unwrap();
+/// Parses the crates, sized by the row of stack numbers under them
+pub fn parse_stacks(stack_strs: &str) -> Result<CrateStacks> {
+    let mut lines: Vec<&str> = stack_strs.split('\n').collect();
+    let numbers = lines.pop().unwrap_or_default();
+
+    let mut num_stacks = 0;
+    for (idx, number) in numbers.split_whitespace().enumerate() {
+        if number != (idx + 1).to_string() {
+            bail!("Expected stack {}, found {:?}", idx + 1, number);
+        }
+        num_stacks += 1;
+    }
+    if num_stacks == 0 {
+        bail!("Expected the stack numbers under the crates");
+    }
+    let mut stacks = CrateStacks::new(num_stacks);
+
+    for line in lines {
+        // crate labels sit at positions 1, 5, 9, ... of each line
+        for (idx, val) in line.chars().skip(1).step_by(4).enumerate() {
             if val != ' ' {
-                stacks.push(idx, val);
+                stacks.push(idx, val)?;
             }
-            idx += 1;
-            pos += 4;
         }
     }
 
@@ -163,36 +218,33 @@ fn parse_stacks(stack_strs: &str) -> Result<CrateStacks> {
     Ok(stacks)
 }
 
-fn parse_instructions(stack_strs: &str) -> Result<Vec<MoveInstruction>> {
+pub fn parse_instructions(stack_strs: &str) -> Result<Vec<MoveInstruction>> {
     let re = Regex::new(r"^move (\d+) from (\d+) to (\d+)$").unwrap();
 
-    let instructions = stack_strs
+    stack_strs
         .split('\n')
-        .into_iter()
         .filter(|s| !s.is_empty())
         .map(|s| {
             let nums = re
                 .captures(s)
-                .with_context(|| format!("Regular expression failed on {}", s))
-                .unwrap();
-            MoveInstruction {
-                count: nums.get(1).unwrap().as_str().parse().unwrap(),
-                from_stack: nums.get(2).unwrap().as_str().parse().unwrap(),
-                to_stack: nums.get(3).unwrap().as_str().parse().unwrap(),
-            }
+                .with_context(|| format!("Regular expression failed on {}", s))?;
+            Ok(MoveInstruction {
+                count: nums[1].parse()?,
+                from_stack: nums[2].parse()?,
+                to_stack: nums[3].parse()?,
+            })
         })
-        .collect();
-
-    Ok(instructions)
+        .collect()
 }
 
 #[cfg(test)]
 mod tests {
     use crate::day05::*;
+    use crate::utils::read_file;
 
     #[test]
     fn test_can_read_file_without_err() {
-        read_file().unwrap();
+        parse_input(&read_file("./resources/day5.txt").unwrap()).unwrap();
     }
 
     #[test]
@@ -209,12 +261,69 @@ mod tests {
         assert_eq!(2, result[1].to_stack);
     }
 
+    const EXAMPLE: &str = "    [D]    
+[N] [C]    
+[Z] [M] [P]
+ 1   2   3 
+
+move 1 from 2 to 1
+move 3 from 1 to 3
+move 2 from 2 to 1
+move 1 from 1 to 2";
+
+    #[test]
+    fn test_example() {
+        assert_eq!("CMZ", part1(EXAMPLE).unwrap());
+        assert_eq!("MCD", part2(EXAMPLE).unwrap());
+    }
+
     #[test]
     fn test_parse_stacks() {
         let input = "[R]     [L] [Q] [B] [B]     [D] [F]\n\
-                           [H] [B] [G] [D] [Q] [Z]     [T] [J]";
+                           [H] [B] [G] [D] [Q] [Z]     [T] [J]\n \
+                            1   2   3   4   5   6   7   8   9 ";
         let result = parse_stacks(input).unwrap();
 
         assert_eq!("RBLQBB DF", result.get_top_as_string());
     }
+
+    #[test]
+    fn test_parse_stacks_wider_than_nine() {
+        let input = "[A] [B] [C] [D] [E] [F] [G] [H] [I] [J] [K]\n \
+                            1   2   3   4   5   6   7   8   9   10  11";
+        let result = parse_stacks(input).unwrap();
+
+        assert_eq!("ABCDEFGHIJK", result.get_top_as_string());
+    }
+
+    #[test]
+    fn test_parse_stacks_malformed() {
+        assert!(parse_stacks("[A] [B]").is_err());
+        assert!(parse_stacks("[A] [B]\n 1   3 ").is_err());
+        assert!(parse_stacks("[A] [B] [C]\n 1   2 ").is_err());
+    }
+
+    #[test]
+    fn test_invalid_moves() {
+        let moves = [
+            ("move 1 from 0 to 1", "There is no stack 0, only 1 to 3"),
+            ("move 1 from 1 to 4", "There is no stack 4, only 1 to 3"),
+            ("move 4 from 2 to 1", "Stack 2 has 3 crates, not 4"),
+        ];
+        let stacks = EXAMPLE.split_once("\n\n").unwrap().0;
+
+        for (instruction, problem) in moves {
+            let input = format!("{}\n\n{}", stacks, instruction);
+            for err in [part1(&input).unwrap_err(), part2(&input).unwrap_err()] {
+                assert_eq!(format!("Could not {}", instruction), err.to_string());
+                assert_eq!(problem, err.root_cause().to_string());
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse_instructions_malformed() {
+        assert!(parse_instructions("move 7 from 3").is_err());
+        assert!(parse_instructions("move 99999999999999999999 from 3 to 9").is_err());
+    }
 }
@@ -1,134 +1,129 @@
 use anyhow::{bail, Context, Result};
-use itertools::Itertools;
+use itertools::{process_results, Itertools};
 use std::collections::HashSet;
-use std::fs::File;
-use std::io::Read;
+use std::io::{self, BufRead};
 
-pub fn run(part: u8) -> Result<()> {
+pub fn solve(part: u8, lines: &str) -> Result<String> {
+    solve_reader(part, lines.as_bytes())
+}
+
+/// Like [`solve`], reading the input a character at a time
+pub fn solve_reader(part: u8, reader: impl BufRead) -> Result<String> {
     if part == 1 {
-        part1()
+        Ok(part1(reader)?.to_string())
     } else {
-        part2()
+        Ok(part2(reader)?.to_string())
     }
 }
 
 #[derive(Debug)]
 struct CharWindow<const WINDOW_SIZE: usize> {
-    string: String,
-    start: usize,
+    /// the last WINDOW_SIZE characters, oldest at `read % WINDOW_SIZE`
+    recent: [u8; WINDOW_SIZE],
+    /// how many characters have been pushed so far
+    read: usize,
     current_values: [u8; 26], // a count for each letter of the alphabet (only dealing with lowercase)
 }
 
-fn char_to_idx(ch: &char) -> usize {
-    *ch as usize - 'a' as usize
+fn char_to_idx(ch: u8) -> Result<usize> {
+    if !ch.is_ascii_lowercase() {
+        bail!("Unexpected character {:?} in the signal", ch as char);
+    }
+    Ok((ch - b'a') as usize)
 }
 
 impl<const N: usize> CharWindow<N> {
-    pub fn new(s: String) -> Self {
-        let mut current_values = [0u8; 26];
-        for ch in s[0..N].chars() {
-            current_values[char_to_idx(&ch)] += 1;
-        }
+    pub fn new() -> Self {
         CharWindow {
-            string: s,
-            start: 0,
-            current_values,
+            recent: [0; N],
+            read: 0,
+            current_values: [0; 26],
         }
     }
 
-    pub fn has_dups(&self) -> bool {
-        self.current_values.iter().any(|i| *i > 1u8)
-    }
-
-    pub fn end(&self) -> usize {
-        self.start + N - 1
-    }
+    /// Adds a character, dropping the oldest one once the window is full
+    pub fn push(&mut self, ch: u8) -> Result<()> {
+        let idx = char_to_idx(ch)?;
 
-    pub fn advance(&mut self) -> bool {
-        if self.start + 1 + N > self.string.len() {
-            return false;
+        let slot = self.read % N;
+        if self.read >= N {
+            self.current_values[(self.recent[slot] - b'a') as usize] -= 1;
         }
+        self.recent[slot] = ch;
+        self.current_values[idx] += 1;
+        self.read += 1;
 
-        let ch_to_remove = &self.string.chars().nth(self.start).unwrap();
-        self.current_values[char_to_idx(ch_to_remove)] -= 1;
+        Ok(())
+    }
 
-        self.start += 1;
+    pub fn has_dups(&self) -> bool {
+        self.current_values.iter().any(|i| *i > 1u8)
+    }
 
-        let ch_to_add = &self.string.chars().nth(self.end()).unwrap();
-        self.current_values[char_to_idx(ch_to_add)] += 1;
+    /// True once the window is full of distinct characters
+    pub fn is_marker(&self) -> bool {
+        self.read >= N && !self.has_dups()
+    }
 
-        // println!(
-        //     "Advanced. Dropped {} (idx {}), added {} (char #{}, idx in string {})",
-        //     ch_to_remove,
-        //     char_to_idx(ch_to_remove),
-        //     ch_to_add,
-        //     char_to_idx(ch_to_add),
-        //     self.end()
-        // );
-        true
+    /// The characters in the window, oldest first
+    pub fn contents(&self) -> String {
+        let split = self.read % N;
+        let (newer, older) = self.recent.split_at(split);
+        older.iter().chain(newer).map(|b| *b as char).collect()
     }
 }
 
-fn part2() -> Result<()> {
+/// The characters of the signal, up to the end of its line
+fn signal_chars(reader: impl BufRead) -> impl Iterator<Item = io::Result<u8>> {
+    reader
+        .bytes()
+        .take_while(|b| !matches!(b, Ok(b'\n') | Ok(b'\r')))
+}
+
+fn part2(signal: impl BufRead) -> Result<usize> {
     println!("Running day6::part2");
 
-    let signal = read_file()?;
+    let mut window: CharWindow<14> = CharWindow::new();
 
-    let mut window: CharWindow<14> = CharWindow::new(signal);
+    for ch in signal_chars(signal) {
+        window.push(ch?)?;
 
-    while window.has_dups() {
-        // println!(
-        //     "Window {} - {} (string {}) has dups. (window={:?}",
-        //     window.start,
-        //     window.end(),
-        //     &window.string.as_str()[window.start..window.end()],
-        //     window.current_values
-        // );
-        if !window.advance() {
-            bail!("advance() returned false. Did not find start of message");
+        if window.is_marker() {
+            println!(
+                "The start of message is {} at {}, the number of characters read is {}",
+                window.contents(),
+                window.read - 14,
+                window.read
+            );
+            return Ok(window.read);
         }
     }
 
-    println!(
-        "The start of message is {} at {}, the number of characters read is {}",
-        &window.string.as_str()[window.start..window.end()],
-        window.start,
-        window.end() + 1
-    );
-    Ok(())
+    bail!("Reached the end of the signal. Did not find start of message");
 }
 
-fn part1() -> Result<()> {
+fn part1(stream: impl BufRead) -> Result<usize> {
     println!("Running day6::part1");
 
-    let stream = read_file()?;
+    let found = process_results(signal_chars(stream), |bytes| {
+        bytes
+            .map(char::from)
+            .tuple_windows::<(_, _, _, _)>()
+            .enumerate()
+            .find(|(_idx, tuple)| !contains_dup(tuple))
+    })?;
+    let (idx, item) = found.context("Did not find the start of packet")?;
 
-    let iter = stream.chars().tuple_windows::<(_, _, _, _)>();
-
-    if let Some((idx, item)) = iter.enumerate().find(|(_idx, tuple)| !contains_dup(tuple)) {
-        println!(
-            "First start of packet found at: {} for input {}{}{}{}",
-            idx + 4,
-            item.0,
-            item.1,
-            item.2,
-            item.3
-        );
-    }
-
-    /*
-    let iter = stream.chars().tuple_windows::<(_, _, _, _)>();
-    for (idx, item) in iter.enumerate() {
-        if contains_dup(&item) {
-            println!(
-                "Start of packet found at: {} for input {}{}{}{}",
-                idx, item.0, item.1, item.2, item.3
-            );
-        }
-    }
-    */
+    println!(
+        "First start of packet found at: {} for input {}{}{}{}",
+        idx + 4,
+        item.0,
+        item.1,
+        item.2,
+        item.3
+    );
 
-    Ok(())
+    Ok(idx + 4)
 }
 
 fn contains_dup((c1, c2, c3, c4): &(char, char, char, char)) -> bool {
@@ -141,22 +136,14 @@ fn contains_dup((c1, c2, c3, c4): &(char, char, char, char)) -> bool {
     set.len() < 4
 }
 
-// Reads the file and returns the CrateStacks along with a list of move instructions
-fn read_file() -> Result<String> {
-    let mut file = File::open("./resources/day6.txt").context("Could not find day6.txt")?;
-    let mut lines = String::new();
-    file.read_to_string(&mut lines)?;
-
-    Ok(lines)
-}
-
 #[cfg(test)]
 mod tests {
     use crate::day06::*;
+    use crate::utils::read_file;
 
     #[test]
     fn test_can_read_file_without_err() {
-        read_file().unwrap();
+        read_file("./resources/day6.txt").unwrap();
     }
 
     #[test]
@@ -171,17 +158,43 @@ mod tests {
         assert!(!contains_dup(&('a', 'b', 'c', 'd')));
     }
 
+    fn window<const N: usize>(s: &str) -> CharWindow<N> {
+        let mut window = CharWindow::new();
+        for ch in s.bytes() {
+            window.push(ch).unwrap();
+        }
+        window
+    }
+
     #[test]
     fn test_has_dups() {
-        let window: CharWindow<14> = CharWindow::new("abcdefghaijklmnopqrstuvwxyz".to_string());
+        let window: CharWindow<14> = window("abcdefghaijklm");
 
         assert!(window.has_dups());
+        assert!(!window.is_marker());
     }
 
     #[test]
     fn test_does_not_has_dups() {
-        let window: CharWindow<14> = CharWindow::new("abcdefghijklmnopqrstuvwxyz".to_string());
+        let window: CharWindow<14> = window("aabcdefghijklmn");
 
         assert!(!window.has_dups());
+        assert!(window.is_marker());
+        assert_eq!("abcdefghijklmn", window.contents());
+    }
+
+    #[test]
+    fn test_push_rejects_other_characters() {
+        let mut window: CharWindow<4> = CharWindow::new();
+
+        assert!(window.push(b'A').is_err());
+    }
+
+    #[test]
+    fn test_examples() {
+        let signal = "mjqjpqmgbljsphdztnvjfqwrcgsmlb\n";
+
+        assert_eq!(7, part1(signal.as_bytes()).unwrap());
+        assert_eq!(19, part2(signal.as_bytes()).unwrap());
     }
 }
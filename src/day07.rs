@@ -1,46 +1,54 @@
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
 use itertools::Itertools;
 use std::collections::HashMap;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::io::BufRead;
 
-pub fn run(part: u8) -> Result<()> {
+pub fn solve(part: u8, lines: &str) -> Result<String> {
+    solve_reader(part, lines.as_bytes())
+}
+
+/// Like [`solve`], reading the input a line at a time
+pub fn solve_reader(part: u8, reader: impl BufRead) -> Result<String> {
     if part == 1 {
-        part1()
+        Ok(part1(reader)?.to_string())
     } else {
-        part2()
+        Ok(part2(reader)?.to_string())
     }
 }
 
-fn part2() -> Result<()> {
+fn part2(reader: impl BufRead) -> Result<u64> {
     println!("Running day7::part2");
 
     let mut term_output = TerminalOutput::new();
-    term_output.parse("./resources/day7.txt")?;
+    term_output.parse(reader)?;
 
-    let total = 70000000;
-    let need = 30000000;
+    let total: u64 = 70000000;
+    let need: u64 = 30000000;
     let used: u64 = term_output.total;
 
-    let target = need - (total - used);
+    let free = total
+        .checked_sub(used)
+        .with_context(|| format!("{} bytes used on a disk of {}", used, total))?;
+    let target = need.saturating_sub(free);
     println!("{} bytes used, need to free {}", used, target);
 
     let to_delete = term_output
         .dir_sizes
         .into_values()
         .sorted()
-        .find(|&x| x > target);
+        .find(|&x| x > target)
+        .context("No directory is large enough to delete")?;
 
-    println!("Deleteing a directory the size of {}", to_delete.unwrap());
+    println!("Deleteing a directory the size of {}", to_delete);
 
-    Ok(())
+    Ok(to_delete)
 }
 
-fn part1() -> Result<()> {
+fn part1(reader: impl BufRead) -> Result<u64> {
     println!("Running day7::part1");
 
     let mut term_output = TerminalOutput::new();
-    term_output.parse("./resources/day7.txt")?;
+    term_output.parse(reader)?;
 
     //println!("{:#?}", term_output);
 
@@ -54,7 +62,7 @@ fn part1() -> Result<()> {
 
     println!("The total of all directories (under 100000) is {}", total);
 
-    Ok(())
+    Ok(total)
 }
 
 #[derive(Debug)]
@@ -77,34 +85,36 @@ impl TerminalOutput {
         }
     }
 
-    // Reads the file and returns the lines
-    fn parse(&mut self, path: &str) -> Result<()> {
-        let file = File::open(path).context("Could not read file")?;
-
-        let reader = BufReader::new(file);
-
+    fn parse(&mut self, reader: impl BufRead) -> Result<()> {
         for (idx, line) in reader.lines().enumerate() {
             let line = line.with_context(|| format!("Could not read line {}", idx))?;
 
-            if line.starts_with("$ cd") {
-                self.change_dir(&line[5..]);
+            if let Some(dir) = line.strip_prefix("$ cd ") {
+                self.change_dir(dir)
+                    .with_context(|| format!("Invalid line {}", idx + 1))?;
             } else if !line.starts_with("$ ls") && !line.starts_with("dir") {
-                let size: u64 = line.split_ascii_whitespace().next().unwrap().parse()?;
+                let size: u64 = line
+                    .split_ascii_whitespace()
+                    .next()
+                    .context("Expected a file size")
+                    .and_then(|size| Ok(size.parse()?))
+                    .with_context(|| format!("Invalid line {}", idx + 1))?;
 
                 //println!("Adding {} to current_size {}", size, self.current_size);
+                self.total = self.total.checked_add(size).with_context(|| {
+                    format!("Invalid line {}: the files are too large", idx + 1)
+                })?;
                 self.current_size += size;
-                self.total += size;
             } else {
                 //println!("Ignore line {}", line);
             }
         }
 
         // Change dir one more time to make sure we add in the current_size
-        self.change_dir("..");
-        Ok(())
+        self.change_dir("..")
     }
 
-    fn change_dir(&mut self, dir: &str) {
+    fn change_dir(&mut self, dir: &str) -> Result<()> {
         // add current directory size to all parents
         for i in 0..self.current_path.len() {
             //dir in &self.current_path {
@@ -122,9 +132,11 @@ impl TerminalOutput {
             self.current_path.push(dir.to_string());
             let path = self.current_path.join("/");
             if self.dir_sizes.insert(path, 0).is_some() {
-                panic!("Changing dir to {} and found a duplicate.", dir)
+                bail!("Changing dir to {} and found a duplicate.", dir)
             }
         }
+
+        Ok(())
     }
 }
 
@@ -145,7 +157,7 @@ mod tests {
             total: 1000,
         };
 
-        t.change_dir("foo");
+        t.change_dir("foo").unwrap();
 
         assert_eq!(&100, t.dir_sizes.get("/").unwrap());
         assert_eq!(&100, t.dir_sizes.get("//test").unwrap());
@@ -154,11 +166,22 @@ mod tests {
         assert_eq!("//test/foo", t.current_path.join("/"));
 
         t.current_size = 11;
-        t.change_dir("..");
+        t.change_dir("..").unwrap();
 
         assert_eq!("//test", t.current_path.join("/"));
         assert_eq!(&111, t.dir_sizes.get("/").unwrap());
         assert_eq!(&111, t.dir_sizes.get("//test").unwrap());
         assert_eq!(&11, t.dir_sizes.get("//test/foo").unwrap());
     }
+
+    #[test]
+    fn test_malformed() {
+        let err = solve(1, "$ cd /\n\n$ ls").unwrap_err();
+        assert_eq!("Invalid line 2", err.to_string());
+
+        assert!(solve(1, "$ cd /\nabc file").is_err());
+        assert!(solve(1, "$ cd /\n$ cd ..\n$ cd /").is_err());
+        assert!(solve(2, "$ cd /\n18446744073709551615 a\n1 b").is_err());
+        assert!(solve(2, "$ cd /\n80000000 a").is_err());
+    }
 }
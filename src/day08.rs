@@ -1,41 +1,56 @@
 use anyhow::bail;
 use anyhow::{Context, Result};
 use std::fmt::Display;
-use std::fs::File;
-use std::io::Read;
 
-pub fn run(part: u8) -> Result<()> {
+pub fn solve(part: u8, lines: &str) -> Result<String> {
     if part == 1 {
-        part1()
+        Ok(part1(lines)?.to_string())
     } else {
-        part2()
+        Ok(part2(lines)?.to_string())
     }
 }
 
-fn part2() -> Result<()> {
+fn part2(lines: &str) -> Result<usize> {
     println!("Running day8::part2");
 
-    let lines = read_file()?;
-    let score = find_most_scenic(&lines)?;
+    let score = find_most_scenic(lines)?;
 
     println!("The score of the most scenic tree is: {}", score);
 
-    Ok(())
+    Ok(score)
 }
 
-fn part1() -> Result<()> {
+fn part1(lines: &str) -> Result<usize> {
     println!("Running day8::part1");
 
-    let lines = read_file()?;
-
-    let count = count_visible(&lines)?;
+    let count = count_visible(lines)?;
 
     println!(
         "There are a total of {} trees visible from the outside.",
         count
     );
 
-    Ok(())
+    Ok(count)
+}
+
+/// Splits the map into rows, checking they are all digits and the same width
+fn parse_rows(lines: &str) -> Result<Vec<&str>> {
+    let rows: Vec<&str> = lines.lines().collect();
+    let width = rows.first().map_or(0, |row| row.len());
+    if width == 0 {
+        bail!("The map is empty");
+    }
+
+    for (row_idx, row) in rows.iter().enumerate() {
+        if row.len() != width {
+            bail!("Invalid line {}: expected {} trees", row_idx + 1, width);
+        }
+        if !row.bytes().all(|b| b.is_ascii_digit()) {
+            bail!("Invalid line {}: expected tree heights", row_idx + 1);
+        }
+    }
+
+    Ok(rows)
 }
 
 struct ForestMap {
@@ -45,14 +60,17 @@ struct ForestMap {
 }
 
 impl ForestMap {
-    fn new(lines: &str) -> Self {
-        let lines: Vec<String> = lines.split('\n').map(|s| s.to_owned()).collect();
-        let width = lines.get(0).unwrap().len();
-
-        ForestMap {
+    fn new(lines: &str) -> Result<Self> {
+        let lines: Vec<String> = parse_rows(lines)?
+            .into_iter()
+            .map(|s| s.to_owned())
+            .collect();
+        let width = lines[0].len();
+
+        Ok(ForestMap {
             forest: lines,
             width,
-        }
+        })
     }
 
     fn total_trees(&self) -> usize {
@@ -222,17 +240,16 @@ impl Visibility {
     }
 }
 
-fn read_file() -> Result<String> {
-    let mut file = File::open("./resources/day8.txt").context("Could not find day6.txt")?;
-    let mut lines = String::new();
-    file.read_to_string(&mut lines)?;
+fn count_visible(lines: &str) -> Result<usize> {
+    let visibility = find_visibility(lines)?;
 
-    Ok(lines)
+    println!("{}", visibility);
+    Ok(visibility.num_visible())
 }
 
-fn count_visible(lines: &str) -> Result<usize> {
-    let lines: Vec<&str> = lines.split('\n').collect();
-    let width = lines.first().unwrap().len();
+fn find_visibility(lines: &str) -> Result<Visibility> {
+    let lines = parse_rows(lines)?;
+    let width = lines[0].len();
     let height = lines.len();
     //let outside_edges = (width * 2) + (height * 2) - 4;
 
@@ -305,23 +322,24 @@ fn count_visible(lines: &str) -> Result<usize> {
         }
     }
 
-    println!("{}", visibility);
-    Ok(visibility.num_visible())
+    Ok(visibility)
 }
 
 fn find_most_scenic(lines: &str) -> Result<usize> {
-    let forest = ForestMap::new(lines);
+    let forest = ForestMap::new(lines)?;
 
     let best_score = (0..forest.total_trees())
         .map(|i| forest.scenic_score(i))
         .max();
 
-    Ok(best_score.unwrap())
+    best_score.context("The map is empty")
 }
 
 #[cfg(test)]
 mod tests {
     use crate::day08::*;
+    use crate::snapshot::assert_snapshot;
+    use crate::utils::read_file;
 
     const EXAMPLE: &str = "30373\n25512\n65332\n33549\n35390";
 
@@ -332,12 +350,19 @@ mod tests {
 
     #[test]
     fn test_part1() {
-        let lines = read_file().unwrap();
+        let lines = read_file("./resources/day8.txt").unwrap();
         let count = count_visible(&lines).unwrap();
 
         assert_eq!(1713, count);
     }
 
+    #[test]
+    fn test_visibility_snapshot() {
+        let visibility = find_visibility(EXAMPLE).unwrap();
+
+        assert_snapshot("day08_visibility", &visibility.to_string());
+    }
+
     #[test]
     fn test_find_most_scenic() {
         assert_eq!(8, find_most_scenic(EXAMPLE).unwrap());
@@ -345,7 +370,7 @@ mod tests {
 
     #[test]
     fn test_scenic_score() {
-        let forest = ForestMap::new(EXAMPLE);
+        let forest = ForestMap::new(EXAMPLE).unwrap();
 
         assert_eq!(4, forest.scenic_score(7));
         assert_eq!(8, forest.scenic_score(17));
@@ -353,10 +378,26 @@ mod tests {
 
     #[test]
     fn test_idx_to_coord() {
-        let forest = ForestMap::new(EXAMPLE);
+        let forest = ForestMap::new(EXAMPLE).unwrap();
 
         assert_eq!((1, 1), forest.idx_to_coord(6));
         assert_eq!((1, 2), forest.idx_to_coord(7));
         assert_eq!((2, 2), forest.idx_to_coord(12));
     }
+
+    #[test]
+    fn test_malformed() {
+        assert!(solve(1, "").is_err());
+        assert!(solve(2, "\n30373").is_err());
+        assert!(solve(1, "303\n25").is_err());
+        assert!(solve(2, "30a").is_err());
+
+        let err = solve(1, "30373\n2551").unwrap_err();
+        assert_eq!("Invalid line 2: expected 5 trees", err.to_string());
+    }
+
+    #[test]
+    fn test_trailing_newline() {
+        assert_eq!("21", solve(1, &format!("{}\n", EXAMPLE)).unwrap());
+    }
 }
@@ -1,17 +1,16 @@
 use std::collections::HashSet;
 
-use anyhow::Result;
+use anyhow::{bail, Context, Result};
 
-use crate::utils::read_file_as_string_vec;
+use crate::frames::{Frame, FrameRecorder, HIGHLIGHT, PRIMARY, SECONDARY};
+use crate::tui::Simulation;
 
-pub fn run(part: u8) -> Result<()> {
+pub fn solve(part: u8, lines: &str) -> Result<String> {
     if part == 1 {
-        part1()?;
+        Ok(part1(lines)?.to_string())
     } else {
-        part2()?;
+        Ok(part2(lines)?.to_string())
     }
-
-    Ok(())
 }
 
 struct RopeSimulation {
@@ -34,43 +33,32 @@ impl RopeSimulation {
     }
 
     fn simulate(&mut self, instructions: &[String]) -> Result<()> {
-        let instructions: Vec<(&str, &str)> = instructions
-            .iter()
-            .map(|i| i.split_once(' ').unwrap())
-            .collect();
-
-        for (dir, steps) in instructions {
-            //
-            let steps: usize = steps.parse()?;
+        for (dir, steps) in parse_instructions(instructions)? {
             self.move_head(dir, steps);
         }
 
         Ok(())
     }
 
-    fn move_head(&mut self, dir: &str, steps: usize) {
-        // get a unit vector of movement
-        let (x, y) = match dir {
-            "R" => (1, 0),
-            "L" => (-1, 0),
-            "U" => (0, -1),
-            "D" => (0, 1),
-            _ => panic!("Found invalid direction: {}", dir),
-        };
-
-        println!("Executing {} {}...", dir, steps);
+    fn move_head(&mut self, dir: (i32, i32), steps: usize) {
+        println!("Executing {:?} {}...", dir, steps);
         for _ in 0..steps {
-            let (cx, cy) = self.knots[0];
-            self.move_knot(0, cx + x, cy + y);
+            self.step_head(dir);
+        }
+    }
 
-            let tail = self.knots[self.knots.len() - 1];
-            let inserted = self.visited.insert(tail);
+    /// Moves the head a single step in the direction of the unit vector
+    fn step_head(&mut self, (x, y): (i32, i32)) {
+        let (cx, cy) = self.knots[0];
+        self.move_knot(0, cx + x, cy + y);
 
-            println!(
-                "Tail is now at ({},{}). new location = {}",
-                tail.0, tail.1, inserted
-            );
-        }
+        let tail = self.knots[self.knots.len() - 1];
+        let inserted = self.visited.insert(tail);
+
+        println!(
+            "Tail is now at ({},{}). new location = {}",
+            tail.0, tail.1, inserted
+        );
     }
 
     fn move_knot(&mut self, knot_idx: usize, x: i32, y: i32) {
@@ -104,12 +92,154 @@ impl RopeSimulation {
     fn num_visited(&self) -> usize {
         self.visited.len()
     }
+
+    fn frame(&self) -> Frame {
+        let mut frame = Frame::new();
+        for coord in &self.visited {
+            frame.set(*coord, SECONDARY);
+        }
+        // draw from the tail forward so the head ends up on top
+        for coord in self.knots.iter().skip(1).rev() {
+            frame.set(*coord, PRIMARY);
+        }
+        frame.set(self.knots[0], HIGHLIGHT);
+
+        frame
+    }
 }
 
-fn part1() -> Result<usize> {
+impl RopeSimulation {
+    /// Draws the rope like the puzzle does, with `H` for the head, then the
+    /// knots numbered from 1 (or `T` when there are only two), `s` for the
+    /// start and `#` for the cells the tail has visited.
+    fn render(&self) -> Vec<String> {
+        let coords = self.visited.iter().chain(self.knots.iter());
+        let min_x = coords.clone().map(|c| c.0).min().unwrap_or(0);
+        let max_x = coords.clone().map(|c| c.0).max().unwrap_or(0);
+        let min_y = coords.clone().map(|c| c.1).min().unwrap_or(0);
+        let max_y = coords.map(|c| c.1).max().unwrap_or(0);
+
+        let width = (max_x - min_x + 1) as usize;
+        let mut grid = vec![vec!['.'; width]; (max_y - min_y + 1) as usize];
+        let mut set = |(x, y): (i32, i32), ch: char| {
+            grid[(y - min_y) as usize][(x - min_x) as usize] = ch;
+        };
+
+        for coord in &self.visited {
+            set(*coord, '#');
+        }
+        set((0, 0), 's');
+        for (idx, coord) in self.knots.iter().enumerate().rev() {
+            let ch = match idx {
+                0 => 'H',
+                1 if self.knots.len() == 2 => 'T',
+                _ => char::from_digit(idx as u32, 36).unwrap_or('*'),
+            };
+            set(*coord, ch);
+        }
+
+        grid.into_iter()
+            .map(|row| row.into_iter().collect())
+            .collect()
+    }
+}
+
+/// Steps the rope one move of the head at a time
+struct RopeStepper {
+    sim: RopeSimulation,
+
+    moves: Vec<(i32, i32)>,
+
+    next: usize,
+}
+
+impl Simulation for RopeStepper {
+    fn step(&mut self) -> Result<bool> {
+        match self.moves.get(self.next) {
+            Some(dir) => {
+                self.sim.step_head(*dir);
+                self.next += 1;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    fn render(&self) -> Vec<String> {
+        self.sim.render()
+    }
+
+    fn status(&self) -> String {
+        format!(
+            "move {}/{} | head {:?} | tail {:?} | visited {}",
+            self.next,
+            self.moves.len(),
+            self.sim.knots[0],
+            self.sim.knots[self.sim.knots.len() - 1],
+            self.sim.num_visited()
+        )
+    }
+}
+
+/// The rope for the terminal viewer
+pub fn simulation(part: u8, lines: &str) -> Result<Box<dyn Simulation>> {
+    let lines: Vec<String> = lines.lines().map(str::to_owned).collect();
+
+    let moves = parse_instructions(&lines)?
+        .into_iter()
+        .flat_map(|(dir, steps)| std::iter::repeat_n(dir, steps))
+        .collect();
+
+    Ok(Box::new(RopeStepper {
+        sim: RopeSimulation::new(if part == 1 { 2 } else { 10 }),
+        moves,
+        next: 0,
+    }))
+}
+
+/// Parses lines like `R 4` into a unit vector of movement and a number of steps
+fn parse_instructions(instructions: &[String]) -> Result<Vec<((i32, i32), usize)>> {
+    instructions
+        .iter()
+        .map(|line| {
+            let (dir, steps) = line
+                .split_once(' ')
+                .with_context(|| format!("Expected a direction and a step count in {}", line))?;
+
+            let dir = match dir {
+                "R" => (1, 0),
+                "L" => (-1, 0),
+                "U" => (0, -1),
+                "D" => (0, 1),
+                _ => bail!("Found invalid direction: {}", dir),
+            };
+
+            Ok((dir, steps.parse()?))
+        })
+        .collect()
+}
+
+/// Records a frame for every step of the head
+pub fn record(part: u8, lines: &str, recorder: &mut FrameRecorder) -> Result<()> {
+    let lines: Vec<String> = lines.lines().map(str::to_owned).collect();
+
+    let mut sim = RopeSimulation::new(if part == 1 { 2 } else { 10 });
+    recorder.record(|| sim.frame())?;
+
+    for (dir, steps) in parse_instructions(&lines)? {
+        for _ in 0..steps {
+            sim.step_head(dir);
+            recorder.record(|| sim.frame())?;
+        }
+    }
+
+    Ok(())
+}
+
+fn part1(lines: &str) -> Result<usize> {
     println!("Running day9::part1");
 
-    let lines = read_file_as_string_vec("./resources/day9.txt")?;
+    let lines: Vec<String> = lines.split('\n').map(str::to_owned).collect();
 
     let mut sim = RopeSimulation::new(2);
 
@@ -123,10 +253,10 @@ fn part1() -> Result<usize> {
     Ok(sim.num_visited())
 }
 
-fn part2() -> Result<usize> {
+fn part2(lines: &str) -> Result<usize> {
     println!("Running day9::part2");
 
-    let lines = read_file_as_string_vec("./resources/day9.txt")?;
+    let lines: Vec<String> = lines.split('\n').map(str::to_owned).collect();
 
     let mut sim = RopeSimulation::new(10);
 
@@ -151,6 +281,7 @@ fn adjacent((x1, y1): &(i32, i32), (x2, y2): &(i32, i32)) -> bool {
 #[cfg(test)]
 mod tests {
     use crate::day09::*;
+    use crate::utils::read_file;
 
     const EXAMPLE_1: &str = "R 4
 U 4
@@ -172,12 +303,14 @@ U 20";
 
     #[test]
     fn test_part1() {
-        assert_eq!(5902, part1().unwrap());
+        let lines = read_file("./resources/day9.txt").unwrap();
+        assert_eq!(5902, part1(&lines).unwrap());
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(2445, part2().unwrap());
+        let lines = read_file("./resources/day9.txt").unwrap();
+        assert_eq!(2445, part2(&lines).unwrap());
     }
 
     #[test]
@@ -212,6 +345,29 @@ U 20";
         assert_eq!(36, sim.num_visited());
     }
 
+    #[test]
+    fn test_render() {
+        let v: Vec<String> = EXAMPLE_1.split('\n').map(|s| s.to_owned()).collect();
+
+        let mut sim = RopeSimulation::new(2);
+        sim.simulate(&v).unwrap();
+
+        let expected = vec!["..##.", "...##", ".TH##", "....#", "s###."];
+        assert_eq!(expected, sim.render());
+    }
+
+    #[test]
+    fn test_parse_instructions() {
+        let v: Vec<String> = vec!["R 4".to_string(), "U 12".to_string()];
+
+        assert_eq!(
+            vec![((1, 0), 4), ((0, -1), 12)],
+            parse_instructions(&v).unwrap()
+        );
+        assert!(parse_instructions(&["X 4".to_string()]).is_err());
+        assert!(parse_instructions(&["R".to_string()]).is_err());
+    }
+
     #[test]
     fn test_adjacent() {
         assert!(adjacent(&(5, 5), &(5, 5)));
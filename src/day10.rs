@@ -1,37 +1,39 @@
 use std::fmt::Display;
+use std::io::BufRead;
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 
-use crate::utils::read_file;
+use crate::frames::{Frame, FrameRecorder, BACKGROUND, HIGHLIGHT, PRIMARY, SECONDARY};
+use crate::tui::Simulation;
 
-pub fn run(part: u8) -> Result<()> {
+pub fn solve(part: u8, lines: &str) -> Result<String> {
+    solve_reader(part, lines.as_bytes())
+}
+
+/// Like [`solve`], reading the input a line at a time
+pub fn solve_reader(part: u8, reader: impl BufRead) -> Result<String> {
     if part == 1 {
-        part1()?;
+        Ok(part1(reader)?.to_string())
     } else {
-        part2()?;
+        Ok(part2(reader)?.to_string())
     }
-
-    Ok(())
 }
 
-fn part1() -> Result<i32> {
+fn part1(reader: impl BufRead) -> Result<i32> {
     println!("Running day10::part1");
 
-    let lines = read_file("./resources/day10.txt")?;
-    let strength = determine_signal_strength(&lines);
+    let strength = determine_signal_strength(reader)?;
 
     println!("Signal strength is {}", strength);
 
     Ok(strength)
 }
 
-fn part2() -> Result<String> {
+fn part2(reader: impl BufRead) -> Result<String> {
     println!("Running day10::part2");
 
-    let lines = read_file("./resources/day10.txt")?;
-
     let mut screen = Screen::new();
-    screen.process(&lines)?;
+    screen.process(reader)?;
 
     let display = format!("{}", screen);
 
@@ -40,13 +42,13 @@ fn part2() -> Result<String> {
     Ok(display)
 }
 
-fn determine_signal_strength(instructions: &str) -> i32 {
-    let v: Vec<&str> = instructions.split('\n').collect();
+fn determine_signal_strength(instructions: impl BufRead) -> Result<i32> {
     let mut signal_strength = 0;
     let mut cycle = 1;
     let mut current_v = 1;
 
-    for inst in v {
+    for inst in instructions.lines() {
+        let inst = inst?;
         cycle += 1;
 
         if is_interesting_cycle(cycle) {
@@ -59,8 +61,7 @@ fn determine_signal_strength(instructions: &str) -> i32 {
         }
 
         if !inst.starts_with("noop") {
-            let (_, param) = inst.split_once(' ').unwrap();
-            let count: i32 = param.parse().unwrap();
+            let count = addx_count(&inst)?;
 
             cycle += 1;
             current_v += count;
@@ -76,7 +77,7 @@ fn determine_signal_strength(instructions: &str) -> i32 {
         }
     }
 
-    signal_strength
+    Ok(signal_strength)
 }
 
 fn is_interesting_cycle(cycle: i32) -> bool {
@@ -87,6 +88,8 @@ struct Screen {
     pixels: Vec<Vec<char>>,
 
     sprite_pos: i32,
+
+    cycle: usize,
 }
 
 impl Screen {
@@ -102,34 +105,31 @@ impl Screen {
         Screen {
             pixels,
             sprite_pos: 1,
+            cycle: 0,
         }
     }
 
-    fn process(&mut self, instructions: &str) -> Result<()> {
-        let instructions: Vec<&str> = instructions.split('\n').collect();
-
-        let mut cycle = 0;
-
-        for inst in instructions {
-            cycle += 1;
-
-            self.draw_cycle(cycle);
-
-            if !inst.starts_with("noop") {
-                cycle += 1;
-
-                self.draw_cycle(cycle);
-                self.move_sprite(inst);
+    fn process(&mut self, instructions: impl BufRead) -> Result<()> {
+        for inst in instructions.lines() {
+            for sprite_move in instruction_moves(&inst?)? {
+                self.tick(sprite_move);
             }
         }
 
         Ok(())
     }
 
-    fn move_sprite(&mut self, instruction: &str) {
-        let (_, param) = instruction.split_once(' ').unwrap();
-        let count: i32 = param.parse().unwrap();
+    /// Runs a single cycle, moving the sprite by `sprite_move` once the pixel is drawn
+    fn tick(&mut self, sprite_move: i32) {
+        self.cycle += 1;
+        self.draw_cycle(self.cycle);
+
+        if sprite_move != 0 {
+            self.move_sprite(sprite_move);
+        }
+    }
 
+    fn move_sprite(&mut self, count: i32) {
         self.sprite_pos += count;
         println!("Moving sprite {} spots to {}", count, self.sprite_pos);
     }
@@ -156,6 +156,129 @@ impl Screen {
     }
 }
 
+impl Screen {
+    fn frame(&self) -> Frame {
+        let mut frame = Frame::new();
+        for (row_idx, row) in self.pixels.iter().enumerate() {
+            for (col_idx, pixel) in row.iter().enumerate() {
+                let color = if *pixel == '#' { PRIMARY } else { BACKGROUND };
+                frame.set((col_idx as i32, row_idx as i32), color);
+            }
+        }
+
+        if self.cycle > 0 {
+            // the beam is on the pixel drawn in the last cycle
+            let row = ((self.cycle - 1) / 40) as i32;
+            let col = ((self.cycle - 1) % 40) as i32;
+            for x in self.sprite_pos - 1..=self.sprite_pos + 1 {
+                if (0..40).contains(&x) && self.pixels[row as usize][x as usize] != '#' {
+                    frame.set((x, row), SECONDARY);
+                }
+            }
+            frame.set((col, row), HIGHLIGHT);
+        }
+
+        frame
+    }
+}
+
+/// Expands an instruction into one entry per cycle, holding how far the
+/// sprite moves at the end of that cycle. `noop` takes one cycle and `addx`
+/// takes two, moving the sprite at the end of the second.
+fn instruction_moves(inst: &str) -> Result<Vec<i32>> {
+    if inst.starts_with("noop") {
+        Ok(vec![0])
+    } else {
+        Ok(vec![0, addx_count(inst)?])
+    }
+}
+
+fn addx_count(inst: &str) -> Result<i32> {
+    let count = inst
+        .strip_prefix("addx ")
+        .with_context(|| format!("Unknown instruction {}", inst))?
+        .trim()
+        .parse()?;
+
+    Ok(count)
+}
+
+/// The sprite moves for every cycle of the instructions, see [`instruction_moves`]
+fn sprite_moves(instructions: impl BufRead) -> Result<Vec<i32>> {
+    let mut moves = Vec::new();
+
+    for inst in instructions.lines() {
+        moves.extend(instruction_moves(&inst?)?);
+    }
+
+    Ok(moves)
+}
+
+/// Steps the CRT one cycle at a time
+struct CrtStepper {
+    screen: Screen,
+
+    sprite_moves: Vec<i32>,
+}
+
+impl Simulation for CrtStepper {
+    fn step(&mut self) -> Result<bool> {
+        match self.sprite_moves.get(self.screen.cycle) {
+            Some(sprite_move) => {
+                self.screen.tick(*sprite_move);
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    fn render(&self) -> Vec<String> {
+        let mut lines: Vec<String> = self.screen.to_string().lines().map(String::from).collect();
+
+        // the row below the screen shows where the sprite is
+        let sprite = (0..40)
+            .map(|x: i32| {
+                if (x - self.screen.sprite_pos).abs() <= 1 {
+                    '^'
+                } else {
+                    ' '
+                }
+            })
+            .collect();
+        lines.push(sprite);
+
+        lines
+    }
+
+    fn status(&self) -> String {
+        format!(
+            "cycle {}/{} | X = {}",
+            self.screen.cycle,
+            self.sprite_moves.len(),
+            self.screen.sprite_pos
+        )
+    }
+}
+
+/// The CRT for the terminal viewer
+pub fn simulation(_part: u8, lines: &str) -> Result<Box<dyn Simulation>> {
+    Ok(Box::new(CrtStepper {
+        screen: Screen::new(),
+        sprite_moves: sprite_moves(lines.as_bytes())?,
+    }))
+}
+
+/// Records a frame for every cycle of the CRT
+pub fn record(_part: u8, lines: &str, recorder: &mut FrameRecorder) -> Result<()> {
+    let mut screen = Screen::new();
+    for sprite_move in sprite_moves(lines.as_bytes())? {
+        screen.tick(sprite_move);
+        recorder.record(|| screen.frame())?;
+    }
+
+    Ok(())
+}
+
 impl Display for Screen {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         for row in &self.pixels {
@@ -172,6 +295,8 @@ impl Display for Screen {
 #[cfg(test)]
 mod tests {
     use crate::day10::*;
+    use crate::snapshot::assert_snapshot;
+    use crate::utils::read_file;
 
     const EXAMPLE: &str = "addx 15
 addx -11
@@ -322,7 +447,8 @@ noop   ";
 
     #[test]
     fn test_part1() {
-        assert_eq!(14060, part1().unwrap());
+        let lines = read_file("./resources/day10.txt").unwrap();
+        assert_eq!(14060, part1(lines.as_bytes()).unwrap());
     }
 
     #[test]
@@ -333,7 +459,8 @@ noop   ";
 ###..####.###..#.#..#....#.#..#.......#.
 #....#..#.#....#.#..#....#.#..#....#..#.
 #....#..#.#....#..#.#....#..#.####..##..";
-        assert_eq!(expected, part2().unwrap().trim());
+        let lines = read_file("./resources/day10.txt").unwrap();
+        assert_eq!(expected, part2(lines.as_bytes()).unwrap().trim());
     }
 
     #[test]
@@ -343,13 +470,16 @@ noop   ";
 
     #[test]
     fn test_example_part1() {
-        assert_eq!(13140, determine_signal_strength(EXAMPLE));
+        assert_eq!(
+            13140,
+            determine_signal_strength(EXAMPLE.as_bytes()).unwrap()
+        );
     }
 
     #[test]
     fn test_example_part2() {
         let mut screen = Screen::new();
-        screen.process(EXAMPLE).unwrap();
+        screen.process(EXAMPLE.as_bytes()).unwrap();
 
         let expected = "##..##..##..##..##..##..##..##..##..##..
 ###...###...###...###...###...###...###.
@@ -364,6 +494,23 @@ noop   ";
         assert_eq!(expected, display.trim());
     }
 
+    #[test]
+    fn test_screen_snapshot() {
+        let mut screen = Screen::new();
+        screen.process(EXAMPLE.as_bytes()).unwrap();
+
+        assert_snapshot("day10_screen_example", &screen.to_string());
+    }
+
+    #[test]
+    fn test_screen_snapshot_input() {
+        let lines = read_file("./resources/day10.txt").unwrap();
+        let mut screen = Screen::new();
+        screen.process(lines.as_bytes()).unwrap();
+
+        assert_snapshot("day10_screen", &screen.to_string());
+    }
+
     #[test]
     fn test_is_interesting_cycle() {
         assert!(is_interesting_cycle(20));
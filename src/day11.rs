@@ -1,18 +1,16 @@
 use anyhow::{bail, Context, Result};
 use itertools::Itertools;
 
-use crate::utils::read_file;
-
-pub fn run(part: u8) -> Result<()> {
-    let lines = read_file("./resources/day11.txt")?;
+use crate::cancel::checkpoint;
+use crate::progress::Progress;
+use crate::tui::Simulation;
 
+pub fn solve(part: u8, lines: &str) -> Result<String> {
     if part == 1 {
-        part1(&lines)?;
+        Ok(part1(lines)?.to_string())
     } else {
-        part2(&lines)?;
+        Ok(part2(lines)?.to_string())
     }
-
-    Ok(())
 }
 
 fn part1(lines: &str) -> Result<u64> {
@@ -20,8 +18,11 @@ fn part1(lines: &str) -> Result<u64> {
 
     let mut game = MonkeyGame::parse_input(lines)?;
 
-    for _ in 0..20 {
+    let mut progress = Progress::new(20, "rounds");
+    for round in 0..20 {
+        checkpoint(|| format!("{}/20 rounds", round))?;
         game.play_round()?;
+        progress.inc();
     }
 
     let monkey_business = game.calc_monkey_business();
@@ -35,8 +36,11 @@ fn part2(lines: &str) -> Result<u64> {
 
     let mut game = MonkeyGame::parse_input(lines)?;
 
-    for _ in 0..10000 {
+    let mut progress = Progress::new(10000, "rounds");
+    for round in 0..10000 {
+        checkpoint(|| format!("{}/10000 rounds", round))?;
         game.play_round2()?;
+        progress.inc();
     }
 
     let monkey_business = game.calc_monkey_business();
@@ -46,7 +50,7 @@ fn part2(lines: &str) -> Result<u64> {
 }
 
 #[derive(Debug, PartialEq)]
-enum Operation {
+pub enum Operation {
     Add(String, String),
     Mul(String, String),
 }
@@ -70,7 +74,7 @@ impl Operation {
     }
 }
 
-struct Monkey {
+pub struct Monkey {
     id: usize,
     items: Vec<i64>,
 
@@ -86,7 +90,7 @@ struct Monkey {
 }
 
 impl Monkey {
-    fn read<I>(it: &mut I) -> Result<Monkey>
+    pub fn read<I>(it: &mut I) -> Result<Monkey>
     where
         I: Iterator,
         I::Item: ToString,
@@ -100,50 +104,58 @@ impl Monkey {
 
         let line = next_line.unwrap().to_string();
 
-        if !line.starts_with("Monkey ") {
-            bail!(
-                "Trying to parse Monkey, but found unexpected input: {}",
-                line
-            );
-        }
-        let (_, second) = line.split_once(' ').unwrap();
-        let id: usize = second[0..second.len() - 1]
+        let id: usize = line
+            .strip_prefix("Monkey ")
+            .and_then(|rest| rest.strip_suffix(':'))
+            .with_context(|| {
+                format!(
+                    "Trying to parse Monkey, but found unexpected input: {}",
+                    line
+                )
+            })?
             .parse()
             .with_context(|| format!("Could not parse monkey ID from {}", line))?;
 
-        let line = it.next().unwrap().to_string();
+        let line = next_monkey_line(it, id)?;
 
         let idx = line
             .find(':')
-            .with_context(|| format!("Expected ':' while processing starting items in {}", line))
-            .unwrap();
+            .with_context(|| format!("Expected ':' while processing starting items in {}", line))?;
         let items: Vec<i64> = line[idx + 1..]
             .split(',')
-            .map(|s| s.trim().parse().unwrap())
-            .collect();
-
-        let line = it.next().unwrap().to_string();
-
-        let idx = line.find('=').unwrap();
-        let line: &str = &line[idx + 1..];
-        let elmts: Vec<&str> = line.split_ascii_whitespace().collect();
-        let operation = match *elmts.get(1).unwrap() {
-            "+" => Operation::Add(elmts[0].to_string(), elmts[2].to_string()),
-            "*" => Operation::Mul(elmts[0].to_string(), elmts[2].to_string()),
-            _ => panic!("Unknown operator in line {}", line),
+            .map(|s| s.trim().parse())
+            .collect::<Result<_, _>>()
+            .with_context(|| format!("Could not parse starting items from {}", line))?;
+
+        let line = next_monkey_line(it, id)?;
+
+        let idx = line
+            .find('=')
+            .with_context(|| format!("Expected '=' in operation {}", line))?;
+        let elmts: Vec<&str> = line[idx + 1..].split_ascii_whitespace().collect();
+        let operation = match elmts[..] {
+            [lhs, "+", rhs] => Operation::Add(lhs.to_string(), rhs.to_string()),
+            [lhs, "*", rhs] => Operation::Mul(lhs.to_string(), rhs.to_string()),
+            _ => bail!("Unknown operation in line {}", line),
         };
 
-        let line = it.next().unwrap().to_string();
+        let line = next_monkey_line(it, id)?;
 
-        let test = line.split_ascii_whitespace().last().unwrap().parse()?;
+        let test = last_number(&line)?;
+        if test <= 0 {
+            bail!(
+                "Monkey {} must test for divisibility by a positive number",
+                id
+            );
+        }
 
-        let line = it.next().unwrap().to_string();
+        let line = next_monkey_line(it, id)?;
 
-        let on_true = line.split_ascii_whitespace().last().unwrap().parse()?;
+        let on_true = last_number(&line)?;
 
-        let line = it.next().unwrap().to_string();
+        let line = next_monkey_line(it, id)?;
 
-        let on_false = line.split_ascii_whitespace().last().unwrap().parse()?;
+        let on_false = last_number(&line)?;
 
         Ok(Monkey {
             id,
@@ -220,7 +232,25 @@ impl Monkey {
         self.items.push(item);
     }
 }
-struct MonkeyGame {
+
+fn next_monkey_line<I>(it: &mut I, id: usize) -> Result<String>
+where
+    I: Iterator,
+    I::Item: ToString,
+{
+    it.next()
+        .map(|line| line.to_string())
+        .with_context(|| format!("Unexpected end of input while reading monkey {}", id))
+}
+
+/// Parses the number at the end of lines like `Test: divisible by 23`
+fn last_number<T: std::str::FromStr>(line: &str) -> Result<T> {
+    line.split_ascii_whitespace()
+        .last()
+        .and_then(|s| s.parse().ok())
+        .with_context(|| format!("Expected a number at the end of {}", line))
+}
+pub struct MonkeyGame {
     monkeys: Vec<Monkey>,
 
     // max limit, used for part 2
@@ -228,23 +258,43 @@ struct MonkeyGame {
 }
 
 impl MonkeyGame {
-    fn parse_input(input: &str) -> Result<MonkeyGame> {
-        let mut it = input.split('\n');
+    pub fn parse_input(input: &str) -> Result<MonkeyGame> {
+        let mut it = input.split('\n').peekable();
 
         let mut results = Vec::new();
         loop {
-            let read_result = Monkey::read(&mut it);
-
-            if let Ok(m) = read_result {
-                results.push(m);
-            } else {
-                let limit = results.iter().map(|m| m.test).product();
-                return Ok(MonkeyGame {
-                    monkeys: results,
-                    limit,
-                });
+            // Only a clean end of input stops the parsing, anything else is a monkey
+            while it.next_if(|line| line.is_empty()).is_some() {}
+            if it.peek().is_none() {
+                break;
             }
+
+            results.push(
+                Monkey::read(&mut it)
+                    .with_context(|| format!("Could not read monkey {}", results.len()))?,
+            );
         }
+
+        if results.is_empty() {
+            bail!("The input has no monkeys");
+        }
+
+        let limit = results
+            .iter()
+            .try_fold(1i64, |acc, m| acc.checked_mul(m.test))
+            .context("The product of the monkey tests overflowed")?;
+
+        if let Some(m) = results
+            .iter()
+            .find(|m| m.on_true >= results.len() || m.on_false >= results.len())
+        {
+            bail!("Monkey {} throws to a monkey that does not exist", m.id);
+        }
+
+        Ok(MonkeyGame {
+            monkeys: results,
+            limit,
+        })
     }
 
     fn play_round(&mut self) -> Result<()> {
@@ -282,9 +332,71 @@ impl MonkeyGame {
     }
 }
 
+/// Steps the game one round at a time
+struct GameStepper {
+    game: MonkeyGame,
+
+    part: u8,
+
+    round: usize,
+    rounds: usize,
+}
+
+impl Simulation for GameStepper {
+    fn step(&mut self) -> Result<bool> {
+        if self.round == self.rounds {
+            return Ok(false);
+        }
+
+        if self.part == 1 {
+            self.game.play_round()?;
+        } else {
+            self.game.play_round2()?;
+        }
+        self.round += 1;
+
+        Ok(true)
+    }
+
+    fn render(&self) -> Vec<String> {
+        self.game
+            .monkeys
+            .iter()
+            .map(|m| {
+                format!(
+                    "Monkey {}: inspected {:>6} | {}",
+                    m.id,
+                    m.inspected,
+                    m.items.iter().join(", ")
+                )
+            })
+            .collect()
+    }
+
+    fn status(&self) -> String {
+        format!(
+            "round {}/{} | monkey business = {}",
+            self.round,
+            self.rounds,
+            self.game.calc_monkey_business()
+        )
+    }
+}
+
+/// The monkey game for the terminal viewer
+pub fn simulation(part: u8, lines: &str) -> Result<Box<dyn Simulation>> {
+    Ok(Box::new(GameStepper {
+        game: MonkeyGame::parse_input(lines)?,
+        part,
+        round: 0,
+        rounds: if part == 1 { 20 } else { 10000 },
+    }))
+}
+
 #[cfg(test)]
 mod tests {
     use crate::day11::*;
+    use crate::utils::read_file;
 
     const EXAMPLE: &str = "Monkey 0:
   Starting items: 79, 98
@@ -359,6 +471,37 @@ Monkey 3:
         assert_eq!(4, results.monkeys.len());
     }
 
+    #[test]
+    fn test_read_monkey_malformed() {
+        assert!(Monkey::read(&mut "Monkey :".split('\n')).is_err());
+        assert!(Monkey::read(&mut "Monkey 0:\n  Starting items: 79, x".split('\n')).is_err());
+        assert!(Monkey::read(&mut EXAMPLE.lines().take(3)).is_err());
+    }
+
+    #[test]
+    fn test_read_input_malformed_monkey() {
+        let truncated = EXAMPLE.replace("  Test: divisible by 19\n", "");
+        let garbage = EXAMPLE.replace("Monkey 1:", "Monkey one:");
+
+        assert!(MonkeyGame::parse_input(&truncated).is_err());
+        assert!(MonkeyGame::parse_input(&garbage).is_err());
+        assert!(MonkeyGame::parse_input("\n\n").is_err());
+        assert_eq!(
+            4,
+            MonkeyGame::parse_input(&format!("{}\n\n", EXAMPLE))
+                .unwrap()
+                .monkeys
+                .len()
+        );
+    }
+
+    #[test]
+    fn test_read_input_throws_to_missing_monkey() {
+        let input = EXAMPLE.replace("throw to monkey 3", "throw to monkey 7");
+
+        assert!(MonkeyGame::parse_input(&input).is_err());
+    }
+
     #[test]
     fn test_operation() {
         assert_eq!(
@@ -1,23 +1,20 @@
 use std::collections::HashSet;
 use std::fmt::Display;
 
-use crate::utils::read_file;
-use anyhow::{Context, Result};
+use crate::frames::{Frame, FrameRecorder, BACKGROUND, HIGHLIGHT, PRIMARY, SECONDARY};
+use anyhow::{bail, Context, Result};
 use itertools::Itertools;
 use petgraph::algo::astar;
 use petgraph::graph::NodeIndex;
+use petgraph::visit::Bfs;
 use petgraph::Graph;
 
-pub fn run(part: u8) -> Result<()> {
-    let lines = read_file("./resources/day12.txt")?;
-
+pub fn solve(part: u8, lines: &str) -> Result<String> {
     if part == 1 {
-        part1(&lines)?;
+        Ok(part1(lines)?.to_string())
     } else {
-        part2(&lines)?;
+        Ok(part2(lines)?.to_string())
     }
-
-    Ok(())
 }
 
 fn part1(lines: &str) -> Result<u32> {
@@ -48,6 +45,12 @@ fn part2(lines: &str) -> Result<u32> {
     Ok(shortest_path)
 }
 
+/// Records a frame for every node the search visits
+pub fn record(part: u8, lines: &str, recorder: &mut FrameRecorder) -> Result<()> {
+    let mut hm = HeightMap::parse(lines);
+    hm.record_search(part, recorder)
+}
+
 /// get a numeric elevation from a char
 fn char_to_elevation(ch: char) -> i32 {
     match ch {
@@ -57,16 +60,16 @@ fn char_to_elevation(ch: char) -> i32 {
     }
 }
 
-struct HeightMap {
+pub struct HeightMap {
     map: Vec<Vec<char>>,
 
     path: Option<HashSet<i32>>,
 }
 
 impl HeightMap {
-    fn parse(lines: &str) -> Self {
+    pub fn parse(lines: &str) -> Self {
         let map: Vec<Vec<char>> = lines
-            .split('\n')
+            .lines()
             .map(|line| line.chars().collect_vec())
             .collect();
 
@@ -74,7 +77,7 @@ impl HeightMap {
     }
 
     fn width(&self) -> usize {
-        self.map.first().unwrap().len()
+        self.map.first().map_or(0, Vec::len)
     }
 
     fn height(&self) -> usize {
@@ -109,13 +112,12 @@ impl HeightMap {
         &self,
         (x, y): (i32, i32),
         is_accessible: fn(i32, i32) -> bool,
-    ) -> Vec<(i32, i32)> {
+    ) -> Result<Vec<(i32, i32)>> {
         let mut result: Vec<(i32, i32)> = Vec::new();
 
-        let current = self
-            .get((x, y))
-            .with_context(|| format!("Trying to get ({},{}) and expecting it to be there.", x, y))
-            .unwrap();
+        let current = self.get((x, y)).with_context(|| {
+            format!("Trying to get ({},{}) and expecting it to be there.", x, y)
+        })?;
 
         let current = char_to_elevation(current);
 
@@ -134,39 +136,41 @@ impl HeightMap {
             }
         }
 
-        result
+        Ok(result)
     }
 
-    fn find_start_and_end_idx(&self) -> (usize, usize) {
-        let mut start = 0;
-        let mut end = 0;
+    fn find_start_and_end_idx(&self) -> Result<(usize, usize)> {
+        let mut start = None;
+        let mut end = None;
         for col_idx in 0..self.width() as i32 {
             for row_idx in 0..self.height() as i32 {
                 if let Some(val) = self.get((col_idx, row_idx)) {
                     if val == 'S' {
-                        start = self.coord_to_idx((col_idx as usize, row_idx as usize));
+                        start = Some(self.coord_to_idx((col_idx as usize, row_idx as usize)));
                     } else if val == 'E' {
-                        end = self.coord_to_idx((col_idx as usize, row_idx as usize));
+                        end = Some(self.coord_to_idx((col_idx as usize, row_idx as usize)));
                     }
                 }
             }
         }
 
-        (start, end)
+        Ok((
+            start.context("The map has no start 'S'")?,
+            end.context("The map has no end 'E'")?,
+        ))
     }
 
     /// Find the shortest path from 'S' to 'E'
     fn find_shortest_path(&mut self) -> Result<u32> {
-        let graph = self.build_graph(|current, next| (next - current) <= 1);
-        let (start, end) = self.find_start_and_end_idx();
+        let graph = self.build_graph(|current, next| (next - current) <= 1)?;
+        let (start, end) = self.find_start_and_end_idx()?;
 
         println!("Searching from {} to {}", start, end);
         let start = NodeIndex::new(start);
         let end = NodeIndex::new(end);
 
         let path = astar(&graph, start, |finish| finish == end, |_| 1, |_| 0)
-            .context("Could not find a path.")
-            .unwrap();
+            .context("Could not find a path.")?;
 
         // println!("{:?}", path);
         self.path = Some(path.1.iter().map(|n| n.index() as i32).collect());
@@ -176,8 +180,8 @@ impl HeightMap {
 
     /// Finds the shortest path from position 'E' to any 'a'
     fn find_hiking_trail(&mut self) -> Result<u32> {
-        let graph = self.build_graph(|current, next| (current - next) <= 1);
-        let (_, end) = self.find_start_and_end_idx();
+        let graph = self.build_graph(|current, next| (current - next) <= 1)?;
+        let (_, end) = self.find_start_and_end_idx()?;
 
         let end = NodeIndex::new(end);
 
@@ -192,8 +196,7 @@ impl HeightMap {
             |_| 1,
             |_| 0,
         )
-        .context("Could not find a path.")
-        .unwrap();
+        .context("Could not find a path.")?;
 
         // println!("{:?}", path);
         self.path = Some(path.1.iter().map(|n| n.index() as i32).collect());
@@ -201,7 +204,71 @@ impl HeightMap {
         Ok(path.0)
     }
 
-    fn build_graph(&mut self, f: fn(i32, i32) -> bool) -> Graph<(usize, usize), i32> {
+    /// Records a breadth first search over the same graph the path finding
+    /// uses, drawing the nodes found so far and the frontier left to visit.
+    /// Part 1 searches up from 'S' to 'E' and part 2 down from 'E' to any 'a'.
+    fn record_search(&mut self, part: u8, recorder: &mut FrameRecorder) -> Result<()> {
+        let (start, end) = self.find_start_and_end_idx()?;
+        let (graph, from) = if part == 1 {
+            (
+                self.build_graph(|current, next| (next - current) <= 1)?,
+                start,
+            )
+        } else {
+            (
+                self.build_graph(|current, next| (current - next) <= 1)?,
+                end,
+            )
+        };
+
+        let mut bfs = Bfs::new(&graph, NodeIndex::new(from));
+        while let Some(node) = bfs.next(&graph) {
+            recorder.record(|| {
+                // looking each node up in the queue would be quadratic
+                let frontier: HashSet<usize> = bfs.stack.iter().map(|n| n.index()).collect();
+
+                let mut frame = Frame::new();
+                for (idx, (x, y)) in graph.node_weights().enumerate() {
+                    let color = if idx == start || idx == end {
+                        PRIMARY
+                    } else if frontier.contains(&idx) {
+                        HIGHLIGHT
+                    } else if bfs.discovered.contains(idx) {
+                        SECONDARY
+                    } else {
+                        BACKGROUND
+                    };
+                    frame.set((*x as i32, *y as i32), color);
+                }
+                frame
+            })?;
+
+            let found = if part == 1 {
+                node.index() == end
+            } else {
+                self.get_by_idx(node.index()) == Some('a')
+            };
+            if found {
+                break;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The graph of moves between neighbouring squares that `f` allows, from
+    /// the height of the current square to the height of the next. Fails
+    /// unless every row is as wide as the first.
+    pub fn build_graph(&self, f: fn(i32, i32) -> bool) -> Result<Graph<(usize, usize), i32>> {
+        if let Some(row_idx) = self.map.iter().position(|row| row.len() != self.width()) {
+            bail!(
+                "Row {} has {} squares, expected {}",
+                row_idx,
+                self.map[row_idx].len(),
+                self.width()
+            );
+        }
+
         let mut graph = Graph::<(usize, usize), i32>::new();
 
         // add all nodes
@@ -216,7 +283,7 @@ impl HeightMap {
         // add edges
         for col_idx in 0..self.width() {
             for row_idx in 0..self.height() {
-                let accessible = self.get_accessible_nodes((col_idx as i32, row_idx as i32), f);
+                let accessible = self.get_accessible_nodes((col_idx as i32, row_idx as i32), f)?;
                 // println!(
                 //     "Accessible from ({}, {}) = {:?}",
                 //     col_idx, row_idx, accessible,
@@ -234,7 +301,8 @@ impl HeightMap {
                 });
             }
         }
-        graph
+
+        Ok(graph)
     }
 }
 
@@ -266,6 +334,8 @@ impl Display for HeightMap {
 #[cfg(test)]
 mod tests {
     use crate::day12::*;
+    use crate::snapshot::assert_snapshot;
+    use crate::utils::read_file;
 
     const EXAMPLE: &str = "Sabqponm
 abcryxxl
@@ -284,6 +354,71 @@ abdefghi";
         assert_eq!(437, part1(&lines).unwrap());
     }
 
+    #[test]
+    fn test_height_map_snapshot() {
+        let hm = HeightMap::parse(EXAMPLE);
+
+        assert_snapshot("day12_height_map", &hm.to_string());
+    }
+
+    #[test]
+    fn test_shortest_path_snapshot() {
+        let mut hm = HeightMap::parse(EXAMPLE);
+        hm.find_shortest_path().unwrap();
+
+        assert_snapshot("day12_shortest_path", &hm.to_string());
+    }
+
+    #[test]
+    fn test_hiking_trail_snapshot() {
+        let mut hm = HeightMap::parse(EXAMPLE);
+        hm.find_hiking_trail().unwrap();
+
+        assert_snapshot("day12_hiking_trail", &hm.to_string());
+    }
+
+    #[test]
+    fn test_record_search() {
+        let mut hm = HeightMap::parse(EXAMPLE);
+        let mut recorder = FrameRecorder::measure(1);
+        hm.record_search(1, &mut recorder).unwrap();
+
+        // one frame per node visited, and the path to 'E' is 31 steps long
+        assert!(recorder.len() > 31);
+        assert!(recorder.len() <= hm.width() * hm.height());
+    }
+
+    #[test]
+    fn test_malformed_maps() {
+        let ragged = HeightMap::parse("Sab\ncd\nefE");
+        assert!(ragged
+            .build_graph(|current, next| (next - current) <= 1)
+            .is_err());
+
+        let longer = HeightMap::parse("Sa\nbcdefg\nEa");
+        assert!(longer
+            .build_graph(|current, next| (next - current) <= 1)
+            .is_err());
+
+        // E is too high to climb to
+        assert!(part1("Sz\nzE").is_err());
+
+        assert_eq!(
+            "The map has no start 'S'",
+            part1("ab\ncE").unwrap_err().to_string()
+        );
+        assert_eq!(
+            "The map has no end 'E'",
+            part2("Sb\ncd").unwrap_err().to_string()
+        );
+        assert!(part1("").is_err());
+    }
+
+    #[test]
+    fn test_trailing_newline() {
+        assert_eq!(31, part1(&format!("{}\n", EXAMPLE)).unwrap());
+    }
+
     #[test]
     fn test_part2_example() {
         assert_eq!(29, part2(EXAMPLE).unwrap());
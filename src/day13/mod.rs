@@ -1,22 +1,16 @@
 use anyhow::Result;
 use itertools::Itertools;
 
-use crate::utils::read_file;
-
-mod data;
+pub mod data;
 
 use data::*;
 
-pub fn run(part: u8) -> Result<()> {
-    let lines = read_file("./resources/day13.txt")?;
-
+pub fn solve(part: u8, lines: &str) -> Result<String> {
     if part == 1 {
-        part1(&lines)?;
+        Ok(part1(lines)?.to_string())
     } else {
-        part2(&lines)?;
+        Ok(part2(lines)?.to_string())
     }
-
-    Ok(())
 }
 
 fn part1(lines: &str) -> Result<usize> {
@@ -72,6 +66,7 @@ fn part2(lines: &str) -> Result<usize> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::utils::read_file;
 
     const EXAMPLE: &str = "[1,1,3,1,1]
 [1,1,5,1,1]
@@ -1,6 +1,8 @@
 use anyhow::Result;
 use std::{collections::HashMap, fmt::Display};
 
+use crate::frames::{Frame, BACKGROUND, HIGHLIGHT, PRIMARY, SECONDARY};
+
 use itertools::Itertools;
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum StructureType {
@@ -129,8 +131,36 @@ impl Scan {
         y -= 1;
         self.grid.insert((*x, y), StructureType::Sand);
 
+        // With a floor the pile can spread past the rock, so grow the bounds to keep it all in view
+        self.min_x = self.min_x.min(*x);
+        self.max_x = self.max_x.max(*x);
+
         Ok((false, (*x, y)))
     }
+
+    pub fn frame(&self) -> Frame {
+        let mut frame = Frame::new();
+
+        if self.with_floor {
+            // the pile can spread as far sideways as the floor is deep
+            let reach = self.max_y as i32;
+            for x in self.source.0 as i32 - reach..=self.source.0 as i32 + reach {
+                frame.set((x, self.max_y as i32), PRIMARY);
+            }
+        }
+
+        for ((x, y), tile) in &self.grid {
+            let color = match tile {
+                StructureType::Rock => PRIMARY,
+                StructureType::Sand => SECONDARY,
+                StructureType::Air => BACKGROUND,
+            };
+            frame.set((*x as i32, *y as i32), color);
+        }
+        frame.set((self.source.0 as i32, self.source.1 as i32), HIGHLIGHT);
+
+        frame
+    }
 }
 
 impl Display for Scan {
@@ -141,6 +171,8 @@ impl Display for Scan {
             for x in start_x..=self.max_x + 1 {
                 if x == self.source.0 && y == self.source.1 {
                     write!(f, "+")?;
+                } else if self.with_floor && y == self.max_y {
+                    write!(f, "{}", StructureType::Rock)?;
                 } else {
                     let d = self.grid.get(&(x, y)).unwrap_or(&StructureType::Air);
                     write!(f, "{}", d)?;
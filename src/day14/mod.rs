@@ -1,20 +1,19 @@
 use anyhow::Result;
 
-use crate::utils::read_file;
+use crate::cancel::checkpoint;
+use crate::frames::FrameRecorder;
+use crate::progress::Progress;
+use crate::tui::Simulation;
 
-mod data;
+pub mod data;
 use data::parser::parse;
 
-pub fn run(part: u8) -> Result<()> {
-    let lines = read_file("./resources/day14.txt")?;
-
+pub fn solve(part: u8, lines: &str) -> Result<String> {
     if part == 1 {
-        part1(&lines)?;
+        Ok(part1(lines)?.to_string())
     } else {
-        part2(&lines)?;
+        Ok(part2(lines)?.to_string())
     }
-
-    Ok(())
 }
 
 fn part1(lines: &str) -> Result<usize> {
@@ -26,10 +25,13 @@ fn part1(lines: &str) -> Result<usize> {
 
     let mut count = 0;
     let mut end = false;
+    let mut progress = Progress::unbounded("grains of sand");
 
     while !end {
+        checkpoint(|| format!("dropping {} grains of sand", count))?;
         (end, _) = scan.simulate_grain_of_sand(&(500, 0))?;
         count += 1;
+        progress.set(count as u64);
     }
     // need to subtract 1 from our count
     count -= 1;
@@ -51,10 +53,13 @@ fn part2(lines: &str) -> Result<usize> {
     let mut count = 0;
     let mut end = false;
     let mut coord = (0, 0);
+    let mut progress = Progress::unbounded("grains of sand");
 
     while !end && coord != (500, 0) {
+        checkpoint(|| format!("dropping {} grains of sand", count))?;
         (end, coord) = scan.simulate_grain_of_sand(&(500, 0))?;
         count += 1;
+        progress.set(count as u64);
 
         // if count % 50 == 0 {
         //     println!("After {} iterations:\n{}\n\n", count, scan);
@@ -67,9 +72,82 @@ fn part2(lines: &str) -> Result<usize> {
     Ok(count)
 }
 
+/// Steps the scan one grain of sand at a time
+struct SandStepper {
+    scan: data::Scan,
+
+    grains: usize,
+
+    done: bool,
+}
+
+impl Simulation for SandStepper {
+    fn step(&mut self) -> Result<bool> {
+        if self.done {
+            return Ok(false);
+        }
+
+        let (end, coord) = self.scan.simulate_grain_of_sand(&(500, 0))?;
+        if end {
+            self.done = true;
+            return Ok(false);
+        }
+        self.grains += 1;
+        self.done = coord == (500, 0);
+
+        Ok(true)
+    }
+
+    fn render(&self) -> Vec<String> {
+        self.scan.to_string().lines().map(String::from).collect()
+    }
+
+    fn status(&self) -> String {
+        format!("{} grains of sand at rest", self.grains)
+    }
+}
+
+/// The falling sand for the terminal viewer
+pub fn simulation(part: u8, lines: &str) -> Result<Box<dyn Simulation>> {
+    let mut scan = data::Scan::new(parse(lines)?);
+    if part != 1 {
+        scan.add_floor();
+    }
+
+    Ok(Box::new(SandStepper {
+        scan,
+        grains: 0,
+        done: false,
+    }))
+}
+
+/// Records a frame for every grain of sand that comes to rest
+pub fn record(part: u8, lines: &str, recorder: &mut FrameRecorder) -> Result<()> {
+    let mut scan = data::Scan::new(parse(lines)?);
+    if part != 1 {
+        scan.add_floor();
+    }
+    recorder.record(|| scan.frame())?;
+
+    loop {
+        let (end, coord) = scan.simulate_grain_of_sand(&(500, 0))?;
+        if end {
+            break;
+        }
+        recorder.record(|| scan.frame())?;
+        if coord == (500, 0) {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::snapshot::assert_snapshot;
+    use crate::utils::read_file;
 
     const EXAMPLE: &str = "498,4 -> 498,6 -> 496,6
 503,4 -> 502,4 -> 502,9 -> 494,9";
@@ -84,6 +162,38 @@ mod tests {
         assert_eq!(93, part2(EXAMPLE).unwrap());
     }
 
+    #[test]
+    fn test_simulation() {
+        let mut sim = simulation(2, EXAMPLE).unwrap();
+        while sim.step().unwrap() {}
+
+        assert_eq!("93 grains of sand at rest", sim.status());
+    }
+
+    #[test]
+    fn test_scan_snapshot() {
+        let scan = data::Scan::new(parse(EXAMPLE).unwrap());
+
+        assert_snapshot("day14_scan", &scan.to_string());
+    }
+
+    #[test]
+    fn test_scan_with_sand_snapshot() {
+        let mut scan = data::Scan::new(parse(EXAMPLE).unwrap());
+        while !scan.simulate_grain_of_sand(&(500, 0)).unwrap().0 {}
+
+        assert_snapshot("day14_scan_with_sand", &scan.to_string());
+    }
+
+    #[test]
+    fn test_scan_with_floor_snapshot() {
+        let mut scan = data::Scan::new(parse(EXAMPLE).unwrap());
+        scan.add_floor();
+        while scan.simulate_grain_of_sand(&(500, 0)).unwrap().1 != (500, 0) {}
+
+        assert_snapshot("day14_scan_with_floor", &scan.to_string());
+    }
+
     #[test]
     fn test_part1() {
         let lines = read_file("./resources/day14.txt").unwrap();
@@ -1,23 +1,27 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 
 use crate::{
+    cancel::checkpoint,
     day15::data::{scan_line, Coord, Sensor, TunnelMap},
-    utils::read_file,
+    params,
+    progress::Progress,
 };
 
-mod data;
+pub mod data;
 use data::parser::parse;
 
-pub fn run(part: u8) -> Result<()> {
-    let lines = read_file("./resources/day15.txt")?;
-
+pub fn solve(part: u8, lines: &str) -> Result<String> {
     if part == 1 {
-        part1(&lines, 2000000)?;
+        Ok(part1(lines, param("row", 2000000)?)?.to_string())
     } else {
-        part2(&lines, 4000000)?;
+        Ok(part2(lines, param("max", 4000000)?)?.to_string())
     }
+}
 
-    Ok(())
+/// A parameter as a coordinate, which has to fit an `i32`
+fn param(name: &str, default: i64) -> Result<i32> {
+    let value = params::get(name, default);
+    i32::try_from(value).with_context(|| format!("Invalid {} {}", name, value))
 }
 
 fn part1(lines: &str, line: i32) -> Result<usize> {
@@ -68,10 +72,10 @@ fn part2(lines: &str, max_bound: i32) -> Result<i64> {
 
     let sensors: Vec<Sensor> = input.iter().map(|i| Sensor::new(i.0, i.1)).collect();
 
+    let mut progress = Progress::new(max_bound as u64, "rows");
     for y in 0..max_bound {
-        if y % 50000 == 0 {
-            println!("Processed {}/{} lines", y, max_bound);
-        }
+        checkpoint(|| format!("scanning {}/{} rows", y, max_bound))?;
+        progress.inc();
 
         if let Some(c) = scan_line(&sensors, y, 0, max_bound) {
             let tuning_freq = c.x() as i64 * 4000000 + c.y() as i64;
@@ -87,6 +91,7 @@ fn part2(lines: &str, max_bound: i32) -> Result<i64> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::snapshot::assert_snapshot;
 
     const EXAMPLE: &str = "Sensor at x=2, y=18: closest beacon is at x=-2, y=15
 Sensor at x=9, y=16: closest beacon is at x=10, y=16
@@ -108,11 +113,36 @@ Sensor at x=20, y=1: closest beacon is at x=15, y=3";
         assert_eq!(26, part1(EXAMPLE, 10).unwrap());
     }
 
+    #[test]
+    fn test_tunnel_map_snapshot() {
+        let input = parse(EXAMPLE).unwrap();
+        let cave_map = TunnelMap::new(&input);
+
+        assert_snapshot("day15_tunnel_map", &cave_map.to_string());
+    }
+
     #[test]
     fn test_part2_example() {
         assert_eq!(56000011, part2(EXAMPLE, 20).unwrap());
     }
 
+    #[test]
+    fn test_params() {
+        let params = params::Params::from([("row".to_string(), 10)]);
+        assert_eq!(
+            "26",
+            params::run_with(&params, || solve(1, EXAMPLE)).unwrap()
+        );
+
+        let params = params::Params::from([("row".to_string(), 1 << 32)]);
+        assert_eq!(
+            "Invalid row 4294967296",
+            params::run_with(&params, || solve(1, EXAMPLE))
+                .unwrap_err()
+                .to_string()
+        );
+    }
+
     // Commented out because it is too slow
     // #[test]
     // fn test_part1() {
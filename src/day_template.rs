@@ -1,17 +1,11 @@
 use anyhow::Result;
 
-use crate::utils::read_file;
-
-pub fn run(part: u8) -> Result<()> {
-    let lines = read_file("./resources/dayX.txt")?;
-
-    if part >= 1 {
-        part1(&lines)?;
+pub fn solve(part: u8, lines: &str) -> Result<String> {
+    if part == 1 {
+        Ok(part1(lines)?.to_string())
     } else {
-        part2(&lines)?;
+        Ok(part2(lines)?.to_string())
     }
-
-    Ok(())
 }
 
 fn part1(lines: &str) -> Result<usize> {
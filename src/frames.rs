@@ -0,0 +1,508 @@
+//! Records the state of a simulation as it runs and exports the frames as
+//! images.
+//!
+//! A simulation draws each step into a [`Frame`] using palette indices
+//! ([`BACKGROUND`], [`PRIMARY`], ...) and hands it to a [`FrameRecorder`].
+//! Frames only list the cells that are drawn, so a recording is made in two
+//! passes over the simulation: the first measures the bounds of every frame
+//! and the second paints each frame straight into the images. That keeps
+//! simulations such as the rope in day 9, which can wander anywhere, from
+//! having to know their size up front, without holding every frame in memory.
+
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use anyhow::{bail, Context, Result};
+use clap::ValueEnum;
+
+/// Palette index for anything that is not drawn
+pub const BACKGROUND: u8 = 0;
+/// Palette index for the main feature (rock, lit pixels, rope knots)
+pub const PRIMARY: u8 = 1;
+/// Palette index for secondary features (sand, visited cells, the sprite)
+pub const SECONDARY: u8 = 2;
+/// Palette index for whatever is moving right now (the head, the beam)
+pub const HIGHLIGHT: u8 = 3;
+
+/// The cells drawn for a single step of a simulation
+#[derive(Debug, Default, Clone)]
+pub struct Frame {
+    cells: Vec<((i32, i32), u8)>,
+}
+
+impl Frame {
+    pub fn new() -> Self {
+        Frame::default()
+    }
+
+    /// Draws a cell. Later calls for the same cell draw on top of earlier ones.
+    pub fn set(&mut self, coord: (i32, i32), color: u8) {
+        self.cells.push((coord, color));
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Palette {
+    colors: Vec<[u8; 3]>,
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Palette {
+            colors: vec![
+                [0x10, 0x10, 0x20], // background
+                [0xe0, 0xe0, 0xe0], // primary
+                [0xc2, 0xb2, 0x80], // secondary
+                [0xe0, 0x40, 0x40], // highlight
+            ],
+        }
+    }
+}
+
+impl Palette {
+    fn color(&self, idx: u8) -> Result<[u8; 3]> {
+        self.colors
+            .get(idx as usize)
+            .copied()
+            .with_context(|| format!("The palette has no color {}", idx))
+    }
+}
+
+/// Parses a comma separated list of hex colors, e.g. `101020,ffffff,c2b280,e04040`.
+/// The first color is the background.
+impl FromStr for Palette {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let colors = s
+            .split(',')
+            .map(|hex| {
+                let hex = hex.trim().trim_start_matches('#');
+                if hex.len() != 6 || !hex.is_ascii() {
+                    bail!("Expected a color like ff8800 but found {}", hex);
+                }
+                let channel = |i: usize| {
+                    u8::from_str_radix(&hex[i..i + 2], 16)
+                        .with_context(|| format!("Invalid hex color {}", hex))
+                };
+
+                Ok([channel(0)?, channel(2)?, channel(4)?])
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        if colors.len() > 256 {
+            bail!("A palette can have at most 256 colors");
+        }
+
+        Ok(Palette { colors })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    /// A numbered sequence of PPM images
+    Ppm,
+    /// A single animated GIF
+    Gif,
+}
+
+#[derive(Debug, Clone)]
+pub struct ExportOptions {
+    pub format: Format,
+
+    /// Width and height in pixels of each cell
+    pub cell_size: usize,
+
+    pub palette: Palette,
+
+    /// Time between GIF frames, in hundredths of a second
+    pub delay: u16,
+}
+
+impl Default for ExportOptions {
+    fn default() -> Self {
+        ExportOptions {
+            format: Format::Gif,
+            cell_size: 4,
+            palette: Palette::default(),
+            delay: 5,
+        }
+    }
+}
+
+/// What a measuring [`FrameRecorder`] found out about a recording
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Measurements {
+    /// Smallest and largest coordinates drawn in any frame
+    pub bounds: ((i32, i32), (i32, i32)),
+
+    /// The largest palette index drawn
+    pub max_color: u8,
+
+    pub frames: usize,
+}
+
+/// Where the recorded frames go
+enum Sink {
+    /// Nowhere, only their bounds and colors are kept
+    Measure {
+        bounds: Option<((i32, i32), (i32, i32))>,
+        max_color: u8,
+    },
+    Ppm {
+        dir: PathBuf,
+        canvas: Canvas,
+        palette: Palette,
+    },
+    Gif {
+        encoder: Box<gif::Encoder<BufWriter<File>>>,
+        canvas: Canvas,
+        delay: u16,
+    },
+}
+
+pub struct FrameRecorder {
+    /// only every Nth step is kept
+    every: usize,
+
+    step: usize,
+
+    /// How many frames have been kept
+    frames: usize,
+
+    sink: Sink,
+}
+
+impl FrameRecorder {
+    /// A recorder for the first pass, which keeps no frames and only measures
+    /// them
+    pub fn measure(every: usize) -> Self {
+        FrameRecorder {
+            every: every.max(1),
+            step: 0,
+            frames: 0,
+            sink: Sink::Measure {
+                bounds: None,
+                max_color: BACKGROUND,
+            },
+        }
+    }
+
+    /// A recorder for the second pass, which writes each frame into `dir` as
+    /// it is recorded, on a canvas sized from the first pass. Fails when the
+    /// palette has fewer colors than the first pass drew with.
+    pub fn export(
+        every: usize,
+        dir: &Path,
+        options: &ExportOptions,
+        measured: &Measurements,
+    ) -> Result<Self> {
+        let colors = options.palette.colors.len();
+        if measured.max_color as usize >= colors {
+            bail!(
+                "The palette has {} colors, but the simulation draws with {}",
+                colors,
+                measured.max_color as usize + 1
+            );
+        }
+
+        fs::create_dir_all(dir).with_context(|| format!("Could not create {}", dir.display()))?;
+        let canvas = Canvas::new(measured.bounds, options.cell_size.max(1));
+
+        let sink = match options.format {
+            Format::Ppm => Sink::Ppm {
+                dir: dir.to_path_buf(),
+                canvas,
+                palette: options.palette.clone(),
+            },
+            Format::Gif => {
+                let (Ok(width), Ok(height)) =
+                    (u16::try_from(canvas.width), u16::try_from(canvas.height))
+                else {
+                    bail!(
+                        "A {}x{} image is too large for a GIF, try a smaller cell size",
+                        canvas.width,
+                        canvas.height
+                    );
+                };
+
+                let palette: Vec<u8> = options.palette.colors.iter().flatten().copied().collect();
+                let path = dir.join("animation.gif");
+                let file = File::create(&path)
+                    .with_context(|| format!("Could not create {}", path.display()))?;
+                let mut encoder = gif::Encoder::new(BufWriter::new(file), width, height, &palette)?;
+                encoder.set_repeat(gif::Repeat::Infinite)?;
+
+                Sink::Gif {
+                    encoder: Box::new(encoder),
+                    canvas,
+                    delay: options.delay,
+                }
+            }
+        };
+
+        Ok(FrameRecorder {
+            every: every.max(1),
+            step: 0,
+            frames: 0,
+            sink,
+        })
+    }
+
+    /// Counts a step of the simulation, drawing a frame for it if it is one
+    /// of the steps being kept.
+    pub fn record<F>(&mut self, draw: F) -> Result<()>
+    where
+        F: FnOnce() -> Frame,
+    {
+        if self.step.is_multiple_of(self.every) {
+            let frame = draw();
+            self.write(&frame)
+                .with_context(|| format!("Could not record frame {}", self.frames))?;
+            self.frames += 1;
+        }
+        self.step += 1;
+
+        Ok(())
+    }
+
+    fn write(&mut self, frame: &Frame) -> Result<()> {
+        match &mut self.sink {
+            Sink::Measure { bounds, max_color } => {
+                for ((x, y), color) in &frame.cells {
+                    let ((min_x, min_y), (max_x, max_y)) =
+                        bounds.get_or_insert(((*x, *y), (*x, *y)));
+                    *min_x = (*min_x).min(*x);
+                    *min_y = (*min_y).min(*y);
+                    *max_x = (*max_x).max(*x);
+                    *max_y = (*max_y).max(*y);
+                    *max_color = (*max_color).max(*color);
+                }
+            }
+            Sink::Ppm {
+                dir,
+                canvas,
+                palette,
+            } => {
+                let path = dir.join(format!("frame_{:05}.ppm", self.frames));
+                let file = File::create(&path)
+                    .with_context(|| format!("Could not create {}", path.display()))?;
+                let mut out = BufWriter::new(file);
+
+                write!(out, "P6\n{} {}\n255\n", canvas.width, canvas.height)?;
+                for color in canvas.paint(frame)? {
+                    out.write_all(&palette.color(color)?)?;
+                }
+                out.flush()?;
+            }
+            Sink::Gif {
+                encoder,
+                canvas,
+                delay,
+            } => {
+                let pixels = canvas.paint(frame)?;
+                let mut image = gif::Frame::from_indexed_pixels(
+                    canvas.width as u16,
+                    canvas.height as u16,
+                    &pixels,
+                    None,
+                );
+                image.delay = *delay;
+                encoder.write_frame(&image)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// How many frames have been kept so far
+    pub fn len(&self) -> usize {
+        self.frames
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames == 0
+    }
+
+    /// What the first pass measured
+    pub fn measurements(&self) -> Result<Measurements> {
+        let Sink::Measure { bounds, max_color } = &self.sink else {
+            bail!("Only a measuring recorder has measurements");
+        };
+
+        Ok(Measurements {
+            bounds: bounds.context("No frames were recorded")?,
+            max_color: *max_color,
+            frames: self.frames,
+        })
+    }
+
+    /// Flushes whatever the second pass has left to write
+    pub fn finish(self) -> Result<()> {
+        if let Sink::Gif { encoder, .. } = self.sink {
+            encoder.into_inner()?.flush()?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The area covered by a recording, in pixels
+struct Canvas {
+    origin: (i32, i32),
+    width: usize,
+    height: usize,
+    cell_size: usize,
+}
+
+impl Canvas {
+    /// Sizes a canvas to cover `bounds`, inclusive
+    fn new(((min_x, min_y), (max_x, max_y)): ((i32, i32), (i32, i32)), cell_size: usize) -> Self {
+        Canvas {
+            origin: (min_x, min_y),
+            width: (max_x - min_x + 1) as usize * cell_size,
+            height: (max_y - min_y + 1) as usize * cell_size,
+            cell_size,
+        }
+    }
+
+    /// Paints a frame as a row-major list of palette indices, one per pixel.
+    /// Fails if the frame draws outside the canvas, which happens when the
+    /// second pass does not draw what the first pass measured.
+    fn paint(&self, frame: &Frame) -> Result<Vec<u8>> {
+        let mut pixels = vec![BACKGROUND; self.width * self.height];
+        let (cols, rows) = (self.width / self.cell_size, self.height / self.cell_size);
+
+        for ((x, y), color) in &frame.cells {
+            let (col, row) = (x - self.origin.0, y - self.origin.1);
+            if col < 0 || row < 0 || col as usize >= cols || row as usize >= rows {
+                bail!("({}, {}) is outside the measured bounds", x, y);
+            }
+
+            let col = col as usize * self.cell_size;
+            let row = row as usize * self.cell_size;
+            for dy in 0..self.cell_size {
+                let start = (row + dy) * self.width + col;
+                pixels[start..start + self.cell_size].fill(*color);
+            }
+        }
+
+        Ok(pixels)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn measure(frames: Vec<Frame>) -> Measurements {
+        let mut recorder = FrameRecorder::measure(1);
+        for frame in frames {
+            recorder.record(|| frame).unwrap();
+        }
+        recorder.measurements().unwrap()
+    }
+
+    #[test]
+    fn test_record_every() {
+        let mut recorder = FrameRecorder::measure(3);
+        for _ in 0..10 {
+            recorder.record(Frame::new).unwrap();
+        }
+
+        // steps 0, 3, 6 and 9
+        assert_eq!(4, recorder.len());
+    }
+
+    #[test]
+    fn test_paint() {
+        let mut frame = Frame::new();
+        frame.set((-1, 0), PRIMARY);
+        frame.set((1, 1), HIGHLIGHT);
+
+        let measured = measure(vec![frame.clone()]);
+        assert_eq!(((-1, 0), (1, 1)), measured.bounds);
+        assert_eq!(HIGHLIGHT, measured.max_color);
+
+        let canvas = Canvas::new(measured.bounds, 2);
+
+        assert_eq!((6, 4), (canvas.width, canvas.height));
+        #[rustfmt::skip]
+        let expected = vec![
+            1, 1, 0, 0, 0, 0,
+            1, 1, 0, 0, 0, 0,
+            0, 0, 0, 0, 3, 3,
+            0, 0, 0, 0, 3, 3,
+        ];
+        assert_eq!(expected, canvas.paint(&frame).unwrap());
+
+        let mut outside = Frame::new();
+        outside.set((2, 0), PRIMARY);
+        assert!(canvas.paint(&outside).is_err());
+    }
+
+    #[test]
+    fn test_measure_without_frames() {
+        assert!(FrameRecorder::measure(1).measurements().is_err());
+    }
+
+    #[test]
+    fn test_export() {
+        let dir = std::env::temp_dir().join(format!("aoc-frames-test-{}", std::process::id()));
+        let mut frame = Frame::new();
+        frame.set((0, 0), PRIMARY);
+        frame.set((2, 1), SECONDARY);
+        let measured = measure(vec![frame.clone(), frame.clone()]);
+
+        for format in [Format::Ppm, Format::Gif] {
+            let options = ExportOptions {
+                format,
+                ..ExportOptions::default()
+            };
+            let mut recorder = FrameRecorder::export(1, &dir, &options, &measured).unwrap();
+            recorder.record(|| frame.clone()).unwrap();
+            recorder.record(|| frame.clone()).unwrap();
+            recorder.finish().unwrap();
+        }
+
+        // 3x2 cells of 4 pixels, 3 bytes per pixel, after the header
+        let ppm = fs::read(dir.join("frame_00001.ppm")).unwrap();
+        assert_eq!(b"P6\n12 8\n255\n".len() + 12 * 8 * 3, ppm.len());
+        assert!(fs::read(dir.join("animation.gif"))
+            .unwrap()
+            .starts_with(b"GIF89a"));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_palette_too_small() {
+        let dir = std::env::temp_dir().join(format!("aoc-palette-test-{}", std::process::id()));
+        let mut frame = Frame::new();
+        frame.set((0, 0), HIGHLIGHT);
+        let options = ExportOptions {
+            palette: "000000,ffffff".parse().unwrap(),
+            ..ExportOptions::default()
+        };
+
+        let result = FrameRecorder::export(1, &dir, &options, &measure(vec![frame]));
+
+        assert_eq!(
+            "The palette has 2 colors, but the simulation draws with 4",
+            result.err().unwrap().to_string()
+        );
+        assert!(!dir.exists());
+    }
+
+    #[test]
+    fn test_parse_palette() {
+        let palette: Palette = "000000,#ff8800".parse().unwrap();
+
+        assert_eq!([0xff, 0x88, 0x00], palette.color(1).unwrap());
+        assert!(palette.color(2).is_err());
+        assert!("00000".parse::<Palette>().is_err());
+        assert!("zz0000".parse::<Palette>().is_err());
+    }
+}
@@ -0,0 +1,324 @@
+//! A local log of the answers submitted on the website and the verdict each
+//! one got, so a rejected answer is not submitted twice.
+//!
+//! The log is a plain text file with one guess per line: the year, the day,
+//! the part, the verdict (`correct`, `too-high`, `too-low` or `wrong`) and the
+//! answer, separated by tabs. Lines starting with `#` are comments.
+
+use std::fmt::Display;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+use anyhow::{bail, Context, Result};
+use clap::ValueEnum;
+
+/// Where the guess log lives, next to `resources/`
+pub const GUESS_LOG: &str = "./guesses.txt";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Verdict {
+    Correct,
+    TooHigh,
+    TooLow,
+    /// Rejected without saying which way it was off
+    Wrong,
+}
+
+impl Display for Verdict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Verdict::Correct => write!(f, "correct"),
+            Verdict::TooHigh => write!(f, "too-high"),
+            Verdict::TooLow => write!(f, "too-low"),
+            Verdict::Wrong => write!(f, "wrong"),
+        }
+    }
+}
+
+impl FromStr for Verdict {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "correct" => Ok(Verdict::Correct),
+            "too-high" => Ok(Verdict::TooHigh),
+            "too-low" => Ok(Verdict::TooLow),
+            "wrong" => Ok(Verdict::Wrong),
+            _ => bail!("Unknown verdict {}", s),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Guess {
+    pub year: u16,
+    pub day: u8,
+    pub part: u8,
+    pub verdict: Verdict,
+    pub answer: String,
+}
+
+/// What the log knows about a newly computed answer
+#[derive(Debug, PartialEq, Eq)]
+pub enum Check {
+    /// The answer was already accepted
+    Correct,
+    /// Nothing is known about the answer
+    Unknown,
+    /// A different answer was already accepted
+    DiffersFromCorrect(String),
+    /// The same answer was already rejected
+    AlreadyRejected(Verdict),
+    /// An answer at or above this was rejected as too high
+    TooHigh(String),
+    /// An answer at or below this was rejected as too low
+    TooLow(String),
+}
+
+impl Display for Check {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Check::Correct => write!(f, "This answer was already accepted."),
+            Check::Unknown => write!(f, "This answer has not been submitted yet."),
+            Check::DiffersFromCorrect(correct) => write!(
+                f,
+                "WARNING: {} was accepted, but this answer is different.",
+                correct
+            ),
+            Check::AlreadyRejected(verdict) => {
+                write!(
+                    f,
+                    "WARNING: this answer was already rejected ({}).",
+                    verdict
+                )
+            }
+            Check::TooHigh(bound) => write!(
+                f,
+                "WARNING: this answer is too high, {} was already rejected as too high.",
+                bound
+            ),
+            Check::TooLow(bound) => write!(
+                f,
+                "WARNING: this answer is too low, {} was already rejected as too low.",
+                bound
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct GuessLog {
+    guesses: Vec<Guess>,
+}
+
+impl GuessLog {
+    /// Reads the log, treating a missing file as an empty log
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(GuessLog::default());
+        }
+
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("Could not read {}", path.display()))?;
+
+        GuessLog::parse(&contents)
+    }
+
+    pub fn parse(contents: &str) -> Result<Self> {
+        let guesses = contents
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty() && !line.starts_with('#'))
+            .map(|(idx, line)| {
+                parse_guess(line).with_context(|| format!("Invalid guess on line {}", idx + 1))
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(GuessLog { guesses })
+    }
+
+    /// Adds a guess to the log and appends it to the file
+    pub fn record(&mut self, path: &Path, guess: Guess) -> Result<()> {
+        if guess.answer.is_empty() || guess.answer.contains(['\t', '\n']) {
+            bail!("Answers must be a single line without tabs, pass the answer with --guess");
+        }
+
+        let mut contents = if path.exists() {
+            fs::read_to_string(path)?
+        } else {
+            "# year\tday\tpart\tverdict\tanswer\n".to_string()
+        };
+        if !contents.is_empty() && !contents.ends_with('\n') {
+            contents.push('\n');
+        }
+        contents.push_str(&format!(
+            "{}\t{}\t{}\t{}\t{}\n",
+            guess.year, guess.day, guess.part, guess.verdict, guess.answer
+        ));
+
+        fs::write(path, contents).with_context(|| format!("Could not write {}", path.display()))?;
+        self.guesses.push(guess);
+
+        Ok(())
+    }
+
+    /// Checks a computed answer against what has already been submitted
+    pub fn check(&self, year: u16, day: u8, part: u8, answer: &str) -> Check {
+        let guesses: Vec<&Guess> = self
+            .guesses
+            .iter()
+            .filter(|g| g.year == year && g.day == day && g.part == part)
+            .collect();
+
+        if let Some(correct) = guesses.iter().find(|g| g.verdict == Verdict::Correct) {
+            return if correct.answer == answer {
+                Check::Correct
+            } else {
+                Check::DiffersFromCorrect(correct.answer.clone())
+            };
+        }
+
+        if let Some(rejected) = guesses.iter().find(|g| g.answer == answer) {
+            return Check::AlreadyRejected(rejected.verdict);
+        }
+
+        // Only numeric answers have bounds
+        let Ok(value) = answer.parse::<i128>() else {
+            return Check::Unknown;
+        };
+        let numeric = |verdict: Verdict| {
+            guesses
+                .iter()
+                .filter(move |g| g.verdict == verdict)
+                .filter_map(|g| g.answer.parse::<i128>().ok().map(|v| (v, &g.answer)))
+        };
+
+        if let Some((_, bound)) = numeric(Verdict::TooHigh)
+            .filter(|(v, _)| value >= *v)
+            .min_by_key(|(v, _)| *v)
+        {
+            return Check::TooHigh(bound.clone());
+        }
+
+        if let Some((_, bound)) = numeric(Verdict::TooLow)
+            .filter(|(v, _)| value <= *v)
+            .max_by_key(|(v, _)| *v)
+        {
+            return Check::TooLow(bound.clone());
+        }
+
+        Check::Unknown
+    }
+}
+
+fn parse_guess(line: &str) -> Result<Guess> {
+    let mut fields = line.splitn(5, '\t');
+    let mut next = |name: &str| {
+        fields
+            .next()
+            .with_context(|| format!("Missing the {} in {}", name, line))
+    };
+
+    Ok(Guess {
+        year: next("year")?.parse()?,
+        day: next("day")?.parse()?,
+        part: next("part")?.parse()?,
+        verdict: next("verdict")?.parse()?,
+        answer: next("answer")?.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LOG: &str = "# year\tday\tpart\tverdict\tanswer
+2022\t1\t2\ttoo-low\t45000
+2022\t1\t2\ttoo-high\t50000
+2022\t1\t2\twrong\t47000
+2022\t5\t1\twrong\tABC
+
+2022\t5\t2\tcorrect\tXYZ
+2023\t1\t2\tcorrect\t46000";
+
+    #[test]
+    fn test_parse() {
+        let log = GuessLog::parse(LOG).unwrap();
+
+        assert_eq!(6, log.guesses.len());
+        assert_eq!(
+            Guess {
+                year: 2022,
+                day: 1,
+                part: 2,
+                verdict: Verdict::TooLow,
+                answer: "45000".to_string()
+            },
+            log.guesses[0]
+        );
+        assert_eq!(2023, log.guesses[5].year);
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert!(GuessLog::parse("2022\t1\t2\tmaybe\t45000").is_err());
+        assert!(GuessLog::parse("2022\t1\t2").is_err());
+        assert!(GuessLog::parse("1\t2\ttoo-low\t45000").is_err());
+    }
+
+    #[test]
+    fn test_record_multi_line_answer() {
+        let mut log = GuessLog::default();
+        let guess = Guess {
+            year: 2022,
+            day: 10,
+            part: 2,
+            verdict: Verdict::Wrong,
+            answer: "##..\n#..#".to_string(),
+        };
+
+        assert!(log.record(Path::new("unused.txt"), guess).is_err());
+    }
+
+    #[test]
+    fn test_check() {
+        let log = GuessLog::parse(LOG).unwrap();
+
+        assert_eq!(Check::Unknown, log.check(2022, 1, 2, "46000"));
+        assert_eq!(
+            Check::AlreadyRejected(Verdict::Wrong),
+            log.check(2022, 1, 2, "47000")
+        );
+        assert_eq!(
+            Check::TooLow("45000".to_string()),
+            log.check(2022, 1, 2, "44000")
+        );
+        assert_eq!(
+            Check::AlreadyRejected(Verdict::TooLow),
+            log.check(2022, 1, 2, "45000")
+        );
+        assert_eq!(
+            Check::TooHigh("50000".to_string()),
+            log.check(2022, 1, 2, "60000")
+        );
+        assert_eq!(Check::Unknown, log.check(2022, 1, 1, "60000"));
+        assert_eq!(Check::Correct, log.check(2023, 1, 2, "46000"));
+    }
+
+    #[test]
+    fn test_check_non_numeric() {
+        let log = GuessLog::parse(LOG).unwrap();
+
+        assert_eq!(
+            Check::AlreadyRejected(Verdict::Wrong),
+            log.check(2022, 5, 1, "ABC")
+        );
+        assert_eq!(Check::Unknown, log.check(2022, 5, 1, "DEF"));
+        assert_eq!(Check::Correct, log.check(2022, 5, 2, "XYZ"));
+        assert_eq!(
+            Check::DiffersFromCorrect("XYZ".to_string()),
+            log.check(2022, 5, 2, "ABC")
+        );
+    }
+}
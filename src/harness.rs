@@ -0,0 +1,211 @@
+//! Runs the solvers registered for a part and collects what each one did, so
+//! the binary only has to decide how to report it.
+
+use std::io::BufRead;
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Result};
+
+use crate::cache::{hash_bytes, AnswerCache, CacheKey};
+use crate::cancel::{self, CancelToken, Cancelled};
+use crate::memory::{self, AllocStats};
+use crate::params::{self, Params};
+use crate::progress;
+use crate::registry::{Registry, BUILT_IN};
+use crate::utils::SilencedStdout;
+use crate::{day01, day02, day03, day04, day06, day07, day10};
+
+#[derive(Debug, Clone, Default)]
+pub struct Options {
+    /// Stop each solver after this long
+    pub timeout: Option<Duration>,
+
+    /// Draw progress bars while solving
+    pub progress: bool,
+
+    /// Hide what the solvers print while they run
+    pub quiet: bool,
+
+    /// Measure what the solvers allocate, see [`crate::memory`]
+    pub memory: bool,
+
+    /// The day's parameters, see [`crate::params`]
+    pub params: Params,
+}
+
+#[derive(Debug)]
+pub enum Outcome {
+    Solved { answer: String, cached: bool },
+    Cancelled(Cancelled),
+    Failed(anyhow::Error),
+}
+
+/// What happened when one solver ran
+#[derive(Debug)]
+pub struct Run {
+    /// The solver, see [`crate::registry::Entry::source`]
+    pub source: String,
+    pub outcome: Outcome,
+    pub elapsed: Duration,
+
+    /// What the solver allocated, when asked for and it actually ran
+    pub memory: Option<AllocStats>,
+}
+
+impl Run {
+    pub fn answer(&self) -> Option<&str> {
+        match &self.outcome {
+            Outcome::Solved { answer, .. } => Some(answer),
+            _ => None,
+        }
+    }
+}
+
+/// Runs every solver registered for a part on `input`, in registration
+/// order. Answers are looked up in and stored to `cache` when there is one.
+pub fn run_part(
+    registry: &Registry,
+    (year, day, part): (u16, u8, u8),
+    input: &str,
+    cache: Option<&AnswerCache>,
+    options: &Options,
+) -> Vec<Run> {
+    // Parameters can change the answer as much as the input does
+    let mut input_hash = hash_bytes(input.as_bytes());
+    if !options.params.is_empty() {
+        input_hash = hash_bytes(format!("{:016x}{:?}", input_hash, options.params).as_bytes());
+    }
+
+    registry
+        .solvers(year, day, part)
+        .iter()
+        .map(|solver| {
+            let key = CacheKey {
+                year,
+                day,
+                part,
+                source: solver.source().to_string(),
+                version: solver.version().to_string(),
+                input_hash,
+            };
+
+            let start = Instant::now();
+
+            if let Some(answer) = cache.and_then(|c| c.get(&key)) {
+                return Run {
+                    source: key.source,
+                    outcome: Outcome::Solved {
+                        answer,
+                        cached: true,
+                    },
+                    elapsed: start.elapsed(),
+                    memory: None,
+                };
+            }
+
+            let (result, stats) = run_solver(options, || solver.solve(year, day, part, input));
+            let elapsed = start.elapsed();
+
+            let outcome = match result {
+                Ok(answer) => match cache.map(|c| c.put(&key, &answer)) {
+                    Some(Err(err)) => Outcome::Failed(err),
+                    _ => Outcome::Solved {
+                        answer,
+                        cached: false,
+                    },
+                },
+                Err(err) => failed(err),
+            };
+
+            Run {
+                source: key.source,
+                outcome,
+                elapsed,
+                memory: options.memory.then_some(stats),
+            }
+        })
+        .collect()
+}
+
+/// Runs the built-in solver for a part on input read a line at a time, for
+/// the days that can stream it. Nothing is cached, since hashing the input
+/// would mean reading it all first.
+pub fn run_stream<R: BufRead>((day, part): (u8, u8), reader: R, options: &Options) -> Result<Run> {
+    let solve: fn(u8, R) -> Result<String> = match day {
+        1 => day01::solve_reader,
+        2 => day02::solve_reader,
+        3 => day03::solve_reader,
+        4 => day04::solve_reader,
+        6 => day06::solve_reader,
+        7 => day07::solve_reader,
+        10 => day10::solve_reader,
+        _ => bail!("Day {} cannot stream its input", day),
+    };
+
+    let start = Instant::now();
+    let (result, stats) = run_solver(options, || solve(part, reader));
+
+    Ok(Run {
+        source: BUILT_IN.to_string(),
+        outcome: match result {
+            Ok(answer) => Outcome::Solved {
+                answer,
+                cached: false,
+            },
+            Err(err) => failed(err),
+        },
+        elapsed: start.elapsed(),
+        memory: options.memory.then_some(stats),
+    })
+}
+
+/// Runs `solve` with everything a solver can look up installed: the
+/// parameters, a token for the timeout and the progress bars
+fn run_solver(
+    options: &Options,
+    solve: impl FnOnce() -> Result<String>,
+) -> (Result<String>, AllocStats) {
+    let token = match options.timeout {
+        Some(timeout) => CancelToken::with_timeout(timeout),
+        None => CancelToken::new(),
+    };
+
+    let _silenced = options.quiet.then(SilencedStdout::new);
+    memory::measure(|| {
+        cancel::run_with(token, || {
+            progress::run_with(options.progress, || {
+                params::run_with(&options.params, solve)
+            })
+        })
+    })
+}
+
+fn failed(err: anyhow::Error) -> Outcome {
+    match err.downcast::<Cancelled>() {
+        Ok(cancelled) => Outcome::Cancelled(cancelled),
+        Err(err) => Outcome::Failed(err),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run_stream_with_params() {
+        let input = "1000\n2000\n\n4000\n\n5000\n6000\n\n7000\n8000\n9000\n\n10000\n";
+        let options = Options {
+            quiet: true,
+            params: Params::from([("top".to_string(), 1)]),
+            ..Default::default()
+        };
+
+        let run = run_stream((1, 2), input.as_bytes(), &options).unwrap();
+        assert_eq!(Some("24000"), run.answer());
+
+        let run = run_stream((1, 2), input.as_bytes(), &Options::default()).unwrap();
+        assert_eq!(Some("45000"), run.answer());
+
+        assert!(run_stream((5, 1), input.as_bytes(), &options).is_err());
+    }
+}
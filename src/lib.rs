@@ -0,0 +1,41 @@
+//! Advent of Code 2022 solutions.
+//!
+//! Each day lives in its own module. The binary in `main.rs` runs them through
+//! the [`registry`], along with any plugins passed on the command line, and
+//! the fuzz targets under `fuzz/` call the input parsers directly.
+
+pub mod cache;
+pub mod cancel;
+pub mod config;
+pub mod day01;
+pub mod day02;
+pub mod day03;
+pub mod day04;
+pub mod day05;
+pub mod day06;
+pub mod day07;
+pub mod day08;
+pub mod day09;
+pub mod day10;
+pub mod day11;
+pub mod day12;
+pub mod day13;
+pub mod day14;
+pub mod day15;
+pub mod frames;
+pub mod guesses;
+pub mod harness;
+pub mod memory;
+pub mod params;
+pub mod plugin;
+pub mod progress;
+pub mod registry;
+pub mod report;
+pub mod tui;
+pub mod utils;
+
+/// The year the day modules in this crate solve
+pub const YEAR: u16 = 2022;
+
+#[cfg(test)]
+mod snapshot;
@@ -1,51 +1,690 @@
-use anyhow::Result;
-use clap::Parser;
-
-mod day01;
-mod day02;
-mod day03;
-mod day04;
-mod day05;
-mod day06;
-mod day07;
-mod day08;
-mod day09;
-mod day10;
-mod day11;
-mod day12;
-mod day13;
-mod day14;
-mod day15;
-mod utils;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, IsTerminal};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+use clap::parser::ValueSource;
+use clap::{ArgMatches, CommandFactory, FromArgMatches, Parser, Subcommand, ValueEnum};
+
+use aoc2022::cache::{AnswerCache, CACHE_DIR};
+use aoc2022::config::Config;
+use aoc2022::frames::{ExportOptions, Format, FrameRecorder, Palette};
+use aoc2022::guesses::{Check, Guess, GuessLog, Verdict, GUESS_LOG};
+use aoc2022::harness::{self, Options, Outcome, Run};
+#[cfg(feature = "alloc-stats")]
+use aoc2022::memory::CountingAllocator;
+use aoc2022::plugin::Plugin;
+use aoc2022::registry::{read_input, Registry, INPUT_DIR};
+use aoc2022::report::{self, Row};
+use aoc2022::tui::Simulation;
+use aoc2022::*;
+
+#[cfg(feature = "alloc-stats")]
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
 
 #[derive(Parser, Debug)]
 #[clap(author, version, about, long_about = None)]
 pub struct Args {
+    #[clap(subcommand)]
+    command: Option<Command>,
+
     #[clap(default_value_t = u8::MAX)]
     day: u8,
 
     #[clap(default_value_t = u8::MAX)]
     part: u8,
+
+    /// The year the day is from
+    #[clap(long, global = true, default_value_t = YEAR)]
+    year: u16,
+
+    /// Read the defaults for these options from this file instead of aoc.toml
+    #[clap(long = "config", global = true)]
+    config_path: Option<PathBuf>,
+
+    /// The directory the puzzle inputs are read from
+    #[clap(long, global = true, default_value = INPUT_DIR)]
+    inputs: PathBuf,
+
+    /// Record the verdict the website gave for an answer. Records the answer
+    /// computed by this run unless --guess is given.
+    #[clap(long, value_enum)]
+    verdict: Option<Verdict>,
+
+    /// The answer that was submitted, if it was not computed by this run
+    #[clap(long, requires = "verdict")]
+    guess: Option<String>,
+
+    /// Only print the answers, not what the solvers print as they run
+    #[clap(long, short, global = true)]
+    quiet: bool,
+
+    /// How to print the answers
+    #[clap(long, value_enum, default_value_t = Output::Text)]
+    output: Output,
+
+    /// Stop a solver after this many seconds and report how far it got
+    #[clap(long, global = true)]
+    timeout: Option<u64>,
+
+    /// Report the peak and total memory each solver allocated. Needs a build
+    /// with the alloc-stats feature.
+    #[clap(long)]
+    memory: bool,
+
+    /// Recompute the answer even if it is cached (the cache is still updated)
+    #[clap(long)]
+    no_cache: bool,
+
+    /// Also run the solvers in this plugin library (can be repeated)
+    #[clap(long, global = true)]
+    plugin: Vec<PathBuf>,
+
+    /// Read the input from this file, or from stdin for `-`, a line at a time
+    /// instead of all at once (days 1, 2, 3, 4, 6, 7 and 10). Plugins and the
+    /// cache are not used.
+    #[clap(long)]
+    stream: Option<PathBuf>,
+
+    /// Step through the simulation in an interactive terminal viewer
+    /// (days 9, 10, 11 and 14)
+    #[clap(long)]
+    tui: bool,
+
+    /// Record the simulation into this directory instead of printing the
+    /// answer (days 9, 10, 12 and 14)
+    #[clap(long)]
+    record: Option<PathBuf>,
+
+    /// Only keep every Nth step of the recording
+    #[clap(long, default_value_t = 1)]
+    every: usize,
+
+    #[clap(long, value_enum, default_value_t = Format::Gif)]
+    format: Format,
+
+    /// Width and height in pixels of each cell of the recording
+    #[clap(long, default_value_t = 4)]
+    cell_size: usize,
+
+    /// Comma separated hex colors for the background, primary, secondary and
+    /// highlight cells, e.g. 101020,e0e0e0,c2b280,e04040
+    #[clap(long)]
+    palette: Option<Palette>,
+
+    /// Time between GIF frames, in hundredths of a second
+    #[clap(long, default_value_t = 5)]
+    delay: u16,
+
+    /// The config the options above were completed from
+    #[clap(skip)]
+    config: Config,
+}
+
+impl Args {
+    /// Parses the command line and fills in every option it does not give
+    /// from the config file
+    fn load() -> Result<Self> {
+        let matches = Args::command().get_matches();
+        let mut args = Args::from_arg_matches(&matches)?;
+
+        let config = Config::load(args.config_path.as_deref())?;
+        args.apply_config(config, &matches)
+            .context("Invalid value in the config")?;
+        Ok(args)
+    }
+
+    fn apply_config(&mut self, config: Config, matches: &ArgMatches) -> Result<()> {
+        let unset = |id: &str| matches.value_source(id) != Some(ValueSource::CommandLine);
+        fn set<T>(field: &mut T, value: Option<T>, unset: bool) {
+            if let Some(value) = value.filter(|_| unset) {
+                *field = value;
+            }
+        }
+
+        set(&mut self.year, config.year, unset("year"));
+        set(&mut self.inputs, config.inputs.clone(), unset("inputs"));
+        set(&mut self.quiet, config.quiet, unset("quiet"));
+        if let Some(output) = &config.output {
+            let output = Output::from_str(output, true).map_err(anyhow::Error::msg)?;
+            set(&mut self.output, Some(output), unset("output"));
+        }
+        set(
+            &mut self.timeout,
+            config.timeout.map(Some),
+            unset("timeout"),
+        );
+        set(
+            &mut self.no_cache,
+            config.cache.map(|cache| !cache),
+            unset("no_cache"),
+        );
+        set(&mut self.memory, config.memory, unset("memory"));
+        set(&mut self.plugin, config.plugins.clone(), unset("plugin"));
+
+        set(&mut self.every, config.every, unset("every"));
+        if let Some(format) = &config.format {
+            let format = Format::from_str(format, true).map_err(anyhow::Error::msg)?;
+            set(&mut self.format, Some(format), unset("format"));
+        }
+        set(&mut self.cell_size, config.cell_size, unset("cell_size"));
+        if let Some(palette) = &config.palette {
+            set(
+                &mut self.palette,
+                Some(Some(palette.parse()?)),
+                unset("palette"),
+            );
+        }
+        set(&mut self.delay, config.delay, unset("delay"));
+
+        self.config = config;
+        Ok(())
+    }
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Run every registered day of the year and rewrite the results table in
+    /// the README
+    Report {
+        #[clap(long, default_value = "README.md")]
+        readme: PathBuf,
+    },
+
+    /// Print statistics about a day's input (day 1)
+    Stats { day: u8 },
+
+    /// List every malformed line of a day's input (day 3)
+    Diagnose { day: u8 },
+
+    /// Plan moving day 1 snacks between elves so the heaviest load is as
+    /// light as possible
+    Rebalance {
+        /// At most this many moves
+        #[clap(long)]
+        max_transfers: Option<usize>,
+    },
+
+    /// Score the day 2 strategy guide under a rules description, such as
+    /// examples/rules/rpsls.toml (the puzzle's rules by default)
+    Play {
+        #[clap(long)]
+        rules: Option<PathBuf>,
+    },
+
+    /// Score the day 2 strategy guide under every meaning X, Y and Z could
+    /// have, and list the ones that give a known total
+    Decrypt {
+        #[clap(long)]
+        target: Option<u64>,
+    },
+
+    /// Fit models of the opponent's moves in the day 2 strategy guide and
+    /// compare counter-strategies against the guide
+    Counter,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Output {
+    Text,
+    /// One JSON object per solver. Implies --quiet.
+    Json,
 }
 
 fn main() -> Result<()> {
-    let args = Args::parse();
-
-    match args.day {
-        1 => day01::run(args.part),
-        2 => day02::run(args.part),
-        3 => day03::run(args.part),
-        4 => day04::run(args.part),
-        5 => day05::run(args.part),
-        6 => day06::run(args.part),
-        7 => day07::run(args.part),
-        8 => day08::run(args.part),
-        9 => day09::run(args.part),
-        10 => day10::run(args.part),
-        11 => day11::run(args.part),
-        12 => day12::run(args.part),
-        13 => day13::run(args.part),
-        14 => day14::run(args.part),
-        _ => day15::run(args.part),
+    let args = Args::load()?;
+
+    if args.tui {
+        return tui(&args);
+    }
+
+    if let Some(dir) = &args.record {
+        return record(&args, dir);
+    }
+
+    if let Some(path) = &args.stream {
+        return stream(&args, path);
+    }
+
+    let mut registry = Registry::with_built_ins();
+    for path in &args.plugin {
+        registry.register_plugin(Plugin::load(path)?);
+    }
+
+    match &args.command {
+        Some(Command::Report { readme }) => return write_report(&args, &registry, readme),
+        Some(Command::Stats { day }) => return stats(&args, *day),
+        Some(Command::Diagnose { day }) => return diagnose(&args, *day),
+        Some(Command::Rebalance { max_transfers }) => return rebalance(&args, *max_transfers),
+        Some(Command::Play { rules }) => return play(&args, rules.as_deref()),
+        Some(Command::Decrypt { target }) => return decrypt(&args, *target),
+        Some(Command::Counter) => return counter(&args),
+        None => {}
+    }
+
+    // Without a day, run the latest part 2 like before the registry existed
+    let year = args.year;
+    let day = if args.day == u8::MAX {
+        registry.last_day(year).unwrap_or(args.day)
+    } else {
+        args.day
+    };
+    let part = if args.part == u8::MAX { 2 } else { args.part };
+
+    let path = Path::new(GUESS_LOG);
+    let mut log = GuessLog::load(path)?;
+
+    let answer = match &args.guess {
+        Some(guess) => guess.clone(),
+        None => solve(&args, &registry, (year, day, part))?,
+    };
+
+    let check = log.check(year, day, part, &answer);
+    if check != Check::Unknown {
+        match args.output {
+            Output::Text => println!("{}", check),
+            Output::Json => report_check((year, day, part), &answer, &check),
+        }
+    }
+
+    if let Some(verdict) = args.verdict {
+        log.record(
+            path,
+            Guess {
+                year,
+                day,
+                part,
+                verdict,
+                answer: answer.clone(),
+            },
+        )?;
+        eprintln!(
+            "Recorded {} as {} for {} day {} part {}",
+            answer, verdict, year, day, part
+        );
+    }
+
+    Ok(())
+}
+
+/// Runs every solver registered for the part and reports each answer.
+/// Returns the answer they agree on.
+fn solve(args: &Args, registry: &Registry, (year, day, part): (u16, u8, u8)) -> Result<String> {
+    let solvers = registry.solvers(year, day, part);
+    if solvers.is_empty() {
+        bail!(
+            "Nothing is registered for {} day {} part {}",
+            year,
+            day,
+            part
+        );
+    }
+
+    if args.memory && !cfg!(feature = "alloc-stats") {
+        bail!("--memory needs a build with `--features alloc-stats`");
+    }
+
+    let input = read_input(&args.inputs, year, day)?;
+    let cache = AnswerCache::new(Path::new(CACHE_DIR));
+    let quiet = args.quiet || args.output == Output::Json;
+    let options = Options {
+        timeout: args.timeout.map(Duration::from_secs),
+        progress: !quiet && io::stderr().is_terminal(),
+        quiet,
+        memory: args.memory,
+        params: args.config.params(day),
+    };
+
+    let runs = harness::run_part(
+        registry,
+        (year, day, part),
+        &input,
+        (!args.no_cache).then_some(&cache),
+        &options,
+    );
+
+    for run in &runs {
+        match args.output {
+            Output::Text => report_text((day, part), run),
+            Output::Json => report_json((year, day, part), run),
+        }
+    }
+
+    let answers: Vec<&str> = runs.iter().filter_map(Run::answer).collect();
+    if answers.len() < runs.len() {
+        bail!(
+            "{} of {} solvers did not finish",
+            runs.len() - answers.len(),
+            runs.len()
+        );
+    }
+    if !answers.iter().all(|a| *a == answers[0]) {
+        bail!("The solvers do not agree on an answer");
+    }
+
+    Ok(answers[0].to_string())
+}
+
+fn report_text((day, part): (u8, u8), run: &Run) {
+    let name = format!("Day {} part {} ({})", day, part, run.source);
+    match &run.outcome {
+        Outcome::Solved {
+            answer,
+            cached: true,
+        } => println!("{}: {} (cached)", name, answer),
+        Outcome::Solved { answer, .. } => println!("{}: {} in {:.2?}", name, answer, run.elapsed),
+        Outcome::Cancelled(cancelled) => println!("{}: {} ({:.2?})", name, cancelled, run.elapsed),
+        Outcome::Failed(err) => println!("{} failed: {:#}", name, err),
+    }
+
+    if let Some(memory) = &run.memory {
+        println!("Memory: {}", memory);
     }
 }
+
+/// Prints the run as a single line of JSON
+fn report_json((year, day, part): (u16, u8, u8), run: &Run) {
+    let mut fields = vec![
+        format!("\"year\":{}", year),
+        format!("\"day\":{}", day),
+        format!("\"part\":{}", part),
+        format!("\"solver\":{}", json_string(&run.source)),
+        format!("\"seconds\":{}", run.elapsed.as_secs_f64()),
+    ];
+
+    match &run.outcome {
+        Outcome::Solved { answer, cached } => {
+            fields.push(format!("\"answer\":{}", json_string(answer)));
+            fields.push(format!("\"cached\":{}", cached));
+        }
+        Outcome::Cancelled(cancelled) => {
+            fields.push(format!("\"timed_out\":{}", cancelled.timed_out));
+            fields.push(format!("\"progress\":{}", json_string(&cancelled.progress)));
+        }
+        Outcome::Failed(err) => {
+            fields.push(format!("\"error\":{}", json_string(&format!("{:#}", err))));
+        }
+    }
+
+    if let Some(memory) = &run.memory {
+        fields.push(format!("\"peak_bytes\":{}", memory.peak_bytes));
+        fields.push(format!("\"total_bytes\":{}", memory.total_bytes));
+        fields.push(format!("\"allocations\":{}", memory.allocations));
+    }
+
+    println!("{{{}}}", fields.join(","));
+}
+
+/// Prints what the guess log says about the answer as a line of JSON
+fn report_check((year, day, part): (u16, u8, u8), answer: &str, check: &Check) {
+    println!(
+        "{{\"year\":{},\"day\":{},\"part\":{},\"answer\":{},\"warning\":{}}}",
+        year,
+        day,
+        part,
+        json_string(answer),
+        json_string(&check.to_string())
+    );
+}
+
+fn json_string(s: &str) -> String {
+    let mut out = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c.is_control() => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// Runs every part registered for the year, without the cache so the
+/// runtimes are real, and rewrites the results table in `readme`
+fn write_report(args: &Args, registry: &Registry, readme: &Path) -> Result<()> {
+    let year = args.year;
+    let mut options = Options {
+        timeout: args.timeout.map(Duration::from_secs),
+        progress: !args.quiet && io::stderr().is_terminal(),
+        quiet: true,
+        memory: false,
+        params: Default::default(),
+    };
+
+    let mut rows: Vec<Row> = Vec::new();
+    for (_, day, part) in registry.parts().filter(|(y, _, _)| *y == year) {
+        if rows.last().is_none_or(|row| row.day != day) {
+            rows.push(Row {
+                day,
+                title: report::title(year, day),
+                source: report::source_path(Path::new("."), year, day),
+                parts: Vec::new(),
+            });
+        }
+
+        let input = read_input(&args.inputs, year, day)?;
+        options.params = args.config.params(day);
+        let runs = harness::run_part(registry, (year, day, part), &input, None, &options);
+        if !args.quiet {
+            for run in &runs {
+                report_text((day, part), run);
+            }
+        }
+
+        rows.last_mut().unwrap().parts.push((part, runs));
+    }
+
+    if rows.is_empty() {
+        bail!("Nothing is registered for {}", year);
+    }
+
+    let text = fs::read_to_string(readme)
+        .with_context(|| format!("Could not read {}", readme.display()))?;
+    let text = report::replace_section(&text, &report::render_table(&rows))
+        .with_context(|| format!("Could not update {}", readme.display()))?;
+    fs::write(readme, text).with_context(|| format!("Could not write {}", readme.display()))?;
+
+    println!(
+        "Wrote results for {} days to {}",
+        rows.len(),
+        readme.display()
+    );
+    Ok(())
+}
+
+/// Solves a part with the day's streaming solver, so the input never has to
+/// fit in memory
+fn stream(args: &Args, path: &Path) -> Result<()> {
+    if args.year != YEAR {
+        bail!("Only the {} days can stream their input", YEAR);
+    }
+
+    let reader: Box<dyn BufRead> = if path == Path::new("-") {
+        Box::new(io::stdin().lock())
+    } else {
+        let file =
+            File::open(path).with_context(|| format!("Could not open {}", path.display()))?;
+        Box::new(BufReader::new(file))
+    };
+    let part = if args.part == u8::MAX { 2 } else { args.part };
+
+    let quiet = args.quiet || args.output == Output::Json;
+    let options = Options {
+        timeout: args.timeout.map(Duration::from_secs),
+        progress: !quiet && io::stderr().is_terminal(),
+        quiet,
+        memory: false,
+        params: args.config.params(args.day),
+    };
+    let run = harness::run_stream((args.day, part), reader, &options)?;
+
+    match args.output {
+        Output::Text => report_text((args.day, part), &run),
+        Output::Json => report_json((YEAR, args.day, part), &run),
+    }
+
+    match run.outcome {
+        Outcome::Failed(_) => bail!("The solver failed"),
+        _ => Ok(()),
+    }
+}
+
+fn stats(args: &Args, day: u8) -> Result<()> {
+    if args.year != YEAR {
+        bail!("Only the {} days have statistics", YEAR);
+    }
+
+    let input = read_input(&args.inputs, YEAR, day)?;
+    match day {
+        1 => {
+            let elves = day01::data::parse_elves(&input)?;
+            print!("{}", day01::stats::calorie_stats(&elves)?);
+        }
+        _ => bail!("Day {} does not have statistics", day),
+    }
+
+    Ok(())
+}
+
+fn diagnose(args: &Args, day: u8) -> Result<()> {
+    if args.year != YEAR {
+        bail!("Only the {} days can be diagnosed", YEAR);
+    }
+
+    let input = read_input(&args.inputs, YEAR, day)?;
+    match day {
+        3 => {
+            let problems = day03::diagnose(input.as_bytes())?;
+            for (line_no, problem) in &problems {
+                println!("Line {}: {}", line_no, problem);
+            }
+            println!("{} malformed rucksacks", problems.len());
+        }
+        _ => bail!("Day {} cannot be diagnosed", day),
+    }
+
+    Ok(())
+}
+
+fn rebalance(args: &Args, max_transfers: Option<usize>) -> Result<()> {
+    if args.year != YEAR {
+        bail!("Only {} day 1 can be rebalanced", YEAR);
+    }
+
+    let elves = day01::data::parse_elves(&read_input(&args.inputs, YEAR, 1)?)?;
+    print!("{}", day01::rebalance::plan(&elves, max_transfers)?);
+
+    Ok(())
+}
+
+fn play(args: &Args, rules: Option<&Path>) -> Result<()> {
+    if args.year != YEAR {
+        bail!("Only {} day 2 can be played", YEAR);
+    }
+
+    let rules = match rules {
+        Some(path) => day02::rules::Rules::load(path)?,
+        None => day02::rules::Rules::default(),
+    };
+    let input = read_input(&args.inputs, YEAR, 2)?;
+
+    for (reading, name) in [
+        (day02::rules::Reading::Shape, "as shapes"),
+        (day02::rules::Reading::Outcome, "as outcomes"),
+    ] {
+        println!(
+            "Second column {}: {}",
+            name,
+            rules.total(input.as_bytes(), reading)?
+        );
+    }
+
+    Ok(())
+}
+
+fn decrypt(args: &Args, target: Option<u64>) -> Result<()> {
+    if args.year != YEAR {
+        bail!("Only {} day 2 can be decrypted", YEAR);
+    }
+
+    let input = read_input(&args.inputs, YEAR, 2)?;
+    print!("{}", day02::decrypt::decrypt(input.as_bytes(), target)?);
+
+    Ok(())
+}
+
+fn counter(args: &Args) -> Result<()> {
+    if args.year != YEAR {
+        bail!("Only {} day 2 has an opponent to model", YEAR);
+    }
+
+    let input = read_input(&args.inputs, YEAR, 2)?;
+    print!("{}", day02::opponent::analyze(input.as_bytes())?);
+
+    Ok(())
+}
+
+fn tui(args: &Args) -> Result<()> {
+    if args.year != YEAR {
+        bail!("Only the {} days have simulations", YEAR);
+    }
+
+    let simulation: fn(u8, &str) -> Result<Box<dyn Simulation>> = match args.day {
+        9 => day09::simulation,
+        10 => day10::simulation,
+        11 => day11::simulation,
+        14 => day14::simulation,
+        _ => bail!(
+            "Day {} does not have a simulation to step through",
+            args.day
+        ),
+    };
+    let part = if args.part == u8::MAX { 2 } else { args.part };
+    let input = read_input(&args.inputs, YEAR, args.day)?;
+    let mut sim = simulation(part, &input)?;
+
+    let title = format!("Day {} part {}", args.day, part);
+    tui::run(&title, sim.as_mut())
+}
+
+fn record(args: &Args, dir: &Path) -> Result<()> {
+    if args.year != YEAR {
+        bail!("Only the {} days have simulations", YEAR);
+    }
+
+    let simulate: fn(u8, &str, &mut FrameRecorder) -> Result<()> = match args.day {
+        9 => day09::record,
+        10 => day10::record,
+        12 => day12::record,
+        14 => day14::record,
+        _ => bail!("Day {} does not have a simulation to record", args.day),
+    };
+    let part = if args.part == u8::MAX { 2 } else { args.part };
+    let input = read_input(&args.inputs, YEAR, args.day)?;
+
+    // The first run sizes the images, the second paints them
+    let mut measure = FrameRecorder::measure(args.every);
+    simulate(part, &input, &mut measure)?;
+    let measured = measure.measurements()?;
+
+    let options = ExportOptions {
+        format: args.format,
+        cell_size: args.cell_size,
+        palette: args.palette.clone().unwrap_or_default(),
+        delay: args.delay,
+    };
+    let mut recorder = FrameRecorder::export(args.every, dir, &options, &measured)?;
+    simulate(part, &input, &mut recorder)?;
+    let frames = recorder.len();
+    recorder.finish()?;
+
+    eprintln!("Recorded {} frames to {}", frames, dir.display());
+    Ok(())
+}
@@ -0,0 +1,207 @@
+//! Memory usage of the solvers.
+//!
+//! [`CountingAllocator`] wraps the system allocator and counts what every
+//! thread allocates. It only counts once it is installed as the global
+//! allocator, which the binary does when built with the `alloc-stats` feature.
+//! [`measure`] then reports what a closure allocated. The counters are per
+//! thread, so solvers running on other threads (tests, for one) do not show up
+//! in each other's numbers.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::fmt::Display;
+
+pub struct CountingAllocator;
+
+#[derive(Debug, Clone, Copy)]
+struct Counters {
+    /// Bytes allocated and not freed yet. This goes negative when the thread
+    /// frees memory that another thread allocated.
+    current: i64,
+    peak: i64,
+    total: u64,
+    allocations: u64,
+}
+
+thread_local! {
+    // const and without a destructor, so the allocator can use it without
+    // allocating itself
+    static COUNTERS: Cell<Counters> = const {
+        Cell::new(Counters {
+            current: 0,
+            peak: 0,
+            total: 0,
+            allocations: 0,
+        })
+    };
+}
+
+fn record_alloc(size: usize) {
+    // try_with, as the thread local is gone while the thread shuts down
+    let _ = COUNTERS.try_with(|c| {
+        let mut counters = c.get();
+        counters.current += size as i64;
+        counters.peak = counters.peak.max(counters.current);
+        counters.total += size as u64;
+        counters.allocations += 1;
+        c.set(counters);
+    });
+}
+
+fn record_dealloc(size: usize) {
+    let _ = COUNTERS.try_with(|c| {
+        let mut counters = c.get();
+        counters.current -= size as i64;
+        c.set(counters);
+    });
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            record_alloc(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            record_alloc(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        record_dealloc(layout.size());
+    }
+
+    /// Counted as a new allocation of `new_size` bytes replacing the old one
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            record_dealloc(layout.size());
+            record_alloc(new_size);
+        }
+        new_ptr
+    }
+}
+
+/// What a closure allocated on its thread
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct AllocStats {
+    /// The most memory that was allocated at once, on top of what was already
+    /// allocated when the closure started
+    pub peak_bytes: u64,
+    pub total_bytes: u64,
+    pub allocations: u64,
+}
+
+impl Display for AllocStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "peak {}, {} in {} allocations",
+            format_bytes(self.peak_bytes),
+            format_bytes(self.total_bytes),
+            self.allocations
+        )
+    }
+}
+
+/// Runs `f` and reports what it allocated. All zeros unless
+/// [`CountingAllocator`] is the global allocator.
+pub fn measure<T>(f: impl FnOnce() -> T) -> (T, AllocStats) {
+    let before = COUNTERS.with(|c| {
+        let mut counters = c.get();
+        let before = counters;
+        counters.peak = counters.current;
+        c.set(counters);
+        before
+    });
+
+    let result = f();
+
+    let stats = COUNTERS.with(|c| {
+        let mut counters = c.get();
+        let stats = AllocStats {
+            peak_bytes: (counters.peak - before.current).max(0) as u64,
+            total_bytes: counters.total - before.total,
+            allocations: counters.allocations - before.allocations,
+        };
+        // keep the peak right for an enclosing measure
+        counters.peak = counters.peak.max(before.peak);
+        c.set(counters);
+        stats
+    });
+
+    (result, stats)
+}
+
+/// Bytes in binary units, e.g. `1.5 MiB`
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", value, UNITS[unit])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[global_allocator]
+    static GLOBAL: CountingAllocator = CountingAllocator;
+
+    #[test]
+    fn test_measure() {
+        let (len, stats) = measure(|| {
+            let first = vec![0u8; 1000];
+            drop(first);
+            let second = vec![0u8; 600];
+            second.len()
+        });
+
+        assert_eq!(600, len);
+        assert_eq!(
+            AllocStats {
+                peak_bytes: 1000,
+                total_bytes: 1600,
+                allocations: 2
+            },
+            stats
+        );
+    }
+
+    #[test]
+    fn test_measure_nested() {
+        let (inner, outer) = measure(|| {
+            let kept = vec![0u8; 100];
+            let (_, inner) = measure(|| vec![0u8; 50]);
+            drop(kept);
+            inner
+        });
+
+        assert_eq!(50, inner.peak_bytes);
+        assert_eq!(150, outer.peak_bytes);
+        assert_eq!(2, outer.allocations);
+    }
+
+    #[test]
+    fn test_format_bytes() {
+        assert_eq!("512 B", format_bytes(512));
+        assert_eq!("1.5 KiB", format_bytes(1536));
+        assert_eq!("3.0 MiB", format_bytes(3 * 1024 * 1024));
+    }
+}
@@ -0,0 +1,45 @@
+//! Puzzle parameters that are not part of the input, such as the row day 15
+//! part 1 scans.
+//!
+//! The harness installs the parameters configured for a day with [`run_with`]
+//! and the solver looks them up by name, falling back to the value the puzzle
+//! uses. Only the built-in days see them; plugins run outside the harness.
+
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+
+/// Parameters by name
+pub type Params = BTreeMap<String, i64>;
+
+thread_local! {
+    static PARAMS: RefCell<Params> = const { RefCell::new(BTreeMap::new()) };
+}
+
+/// Runs `f` with `params` available to [`get`] on this thread
+pub fn run_with<T>(params: &Params, f: impl FnOnce() -> T) -> T {
+    let previous = PARAMS.with(|p| p.replace(params.clone()));
+    let result = f();
+    PARAMS.with(|p| p.replace(previous));
+
+    result
+}
+
+/// The parameter called `name`, or `default` when it is not set
+pub fn get(name: &str, default: i64) -> i64 {
+    PARAMS.with(|p| p.borrow().get(name).copied().unwrap_or(default))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get() {
+        let params = Params::from([("row".to_string(), 10)]);
+
+        assert_eq!(2000000, get("row", 2000000));
+        assert_eq!(10, run_with(&params, || get("row", 2000000)));
+        assert_eq!(20, run_with(&params, || get("max", 20)));
+        assert_eq!(2000000, get("row", 2000000));
+    }
+}
@@ -0,0 +1,311 @@
+//! Loads alternative solutions from dynamic libraries so they can run through
+//! the same harness as the built-in days.
+//!
+//! A plugin is a `cdylib` that exports a function named `aoc_plugin_entry`
+//! returning a pointer to a static [`PluginDescriptor`]. The descriptor lists
+//! the years, days and parts the plugin solves and the functions to call. Only C types
+//! cross the boundary, so plugins do not have to be built with the same
+//! compiler as the binary (or be written in Rust at all).
+//!
+//! The harness reads the input file and passes it to `solve` as a byte buffer
+//! that is only valid for the duration of the call. `solve` returns 0 on
+//! success and stores the answer in `*answer`, or returns any other value and
+//! stores an error message there. Either string is allocated by the plugin and
+//! handed back to its `free_string` once the harness has copied it.
+//!
+//! See `examples/plugin.rs` for a plugin written in Rust, and
+//! `include/aoc_plugin.h` for the same types in C.
+
+use std::ffi::{c_char, c_int, CStr};
+use std::fs;
+use std::path::Path;
+use std::ptr;
+
+use anyhow::{bail, Context, Result};
+use libloading::Library;
+
+use crate::cache::hash_bytes;
+
+/// Bumped whenever [`PluginDescriptor`] changes shape, here and in
+/// `include/aoc_plugin.h`
+pub const ABI_VERSION: u32 = 2;
+
+/// The symbol every plugin exports
+pub const ENTRY_POINT: &str = "aoc_plugin_entry";
+
+/// A part of a puzzle solved by a plugin
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PluginPart {
+    pub year: u16,
+    pub day: u8,
+    pub part: u8,
+}
+
+pub type EntryFn = unsafe extern "C" fn() -> *const PluginDescriptor;
+
+pub type SolveFn = unsafe extern "C" fn(
+    year: u16,
+    day: u8,
+    part: u8,
+    input: *const u8,
+    input_len: usize,
+    answer: *mut *mut c_char,
+) -> c_int;
+
+pub type FreeStringFn = unsafe extern "C" fn(s: *mut c_char);
+
+#[repr(C)]
+pub struct PluginDescriptor {
+    /// Must be [`ABI_VERSION`]
+    pub abi_version: u32,
+
+    /// A nul terminated name to report the answers under
+    pub name: *const c_char,
+
+    pub parts: *const PluginPart,
+    pub parts_len: usize,
+
+    /// Null pointers are rejected when the plugin is loaded
+    pub solve: Option<SolveFn>,
+    pub free_string: Option<FreeStringFn>,
+}
+
+// The descriptor only points at static data, so plugins can keep it in a static
+unsafe impl Sync for PluginDescriptor {}
+
+pub struct Plugin {
+    name: String,
+
+    /// A hash of the library, so answers are recomputed when it is rebuilt
+    version: String,
+
+    parts: Vec<(u16, u8, u8)>,
+    solve: SolveFn,
+    free_string: FreeStringFn,
+
+    // Declared last so the functions above are dropped before the library is unloaded
+    _library: Option<Library>,
+}
+
+impl Plugin {
+    pub fn load(path: &Path) -> Result<Self> {
+        // SAFETY: loading a library runs its initialisers, which we have to
+        // trust. The entry point is checked against the ABI version before any
+        // other function in the descriptor is used.
+        unsafe {
+            let library = Library::new(path)
+                .with_context(|| format!("Could not load plugin {}", path.display()))?;
+            let entry = *library
+                .get::<EntryFn>(ENTRY_POINT.as_bytes())
+                .with_context(|| format!("{} does not export {}", path.display(), ENTRY_POINT))?;
+
+            let mut plugin = Plugin::from_descriptor(entry(), Some(library))
+                .with_context(|| format!("Invalid plugin {}", path.display()))?;
+
+            let contents = fs::read(path)?;
+            plugin.version = format!("{:016x}", hash_bytes(&contents));
+
+            Ok(plugin)
+        }
+    }
+
+    /// # Safety
+    ///
+    /// `descriptor` must be null or point to a descriptor that stays valid
+    /// while `library` is loaded.
+    unsafe fn from_descriptor(
+        descriptor: *const PluginDescriptor,
+        library: Option<Library>,
+    ) -> Result<Self> {
+        let Some(descriptor) = descriptor.as_ref() else {
+            bail!("The entry point returned a null descriptor");
+        };
+
+        if descriptor.abi_version != ABI_VERSION {
+            bail!(
+                "Plugin was built for ABI version {} but this binary supports version {}",
+                descriptor.abi_version,
+                ABI_VERSION
+            );
+        }
+
+        if descriptor.name.is_null() {
+            bail!("Plugin does not have a name");
+        }
+        let name = CStr::from_ptr(descriptor.name)
+            .to_string_lossy()
+            .into_owned();
+
+        let parts = if descriptor.parts_len == 0 {
+            Vec::new()
+        } else if descriptor.parts.is_null() {
+            bail!(
+                "Plugin {} lists {} parts but no parts",
+                name,
+                descriptor.parts_len
+            );
+        } else {
+            std::slice::from_raw_parts(descriptor.parts, descriptor.parts_len)
+                .iter()
+                .map(|p| (p.year, p.day, p.part))
+                .collect()
+        };
+
+        let (Some(solve), Some(free_string)) = (descriptor.solve, descriptor.free_string) else {
+            bail!("Plugin {} is missing solve or free_string", name);
+        };
+
+        Ok(Plugin {
+            name,
+            version: String::new(),
+            parts,
+            solve,
+            free_string,
+            _library: library,
+        })
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn version(&self) -> &str {
+        &self.version
+    }
+
+    /// The years, days and parts this plugin can solve
+    pub fn parts(&self) -> &[(u16, u8, u8)] {
+        &self.parts
+    }
+
+    pub fn solve(&self, year: u16, day: u8, part: u8, input: &str) -> Result<String> {
+        let mut out: *mut c_char = ptr::null_mut();
+
+        // SAFETY: the input buffer outlives the call and `out` is only read
+        // back once the plugin has returned.
+        let code = unsafe { (self.solve)(year, day, part, input.as_ptr(), input.len(), &mut out) };
+
+        let message = if out.is_null() {
+            None
+        } else {
+            // SAFETY: the plugin hands over a nul terminated string and takes
+            // it back in free_string once it has been copied.
+            unsafe {
+                let message = CStr::from_ptr(out).to_string_lossy().into_owned();
+                (self.free_string)(out);
+                Some(message)
+            }
+        };
+
+        match (code, message) {
+            (0, Some(answer)) => Ok(answer),
+            (0, None) => bail!("{} did not return an answer", self.name),
+            (code, message) => bail!(
+                "{} failed with error code {}: {}",
+                self.name,
+                code,
+                message.as_deref().unwrap_or("no message")
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::CString;
+
+    static PARTS: [PluginPart; 2] = [
+        PluginPart {
+            year: 2022,
+            day: 6,
+            part: 1,
+        },
+        PluginPart {
+            year: 2022,
+            day: 6,
+            part: 2,
+        },
+    ];
+
+    /// Returns the input length for part 1 and fails for part 2
+    unsafe extern "C" fn solve(
+        _year: u16,
+        _day: u8,
+        part: u8,
+        _input: *const u8,
+        input_len: usize,
+        answer: *mut *mut c_char,
+    ) -> c_int {
+        let (code, message) = match part {
+            1 => (0, input_len.to_string()),
+            _ => (7, "not solved yet".to_string()),
+        };
+        *answer = CString::new(message).unwrap().into_raw();
+        code
+    }
+
+    unsafe extern "C" fn free_string(s: *mut c_char) {
+        drop(CString::from_raw(s));
+    }
+
+    fn descriptor(abi_version: u32) -> PluginDescriptor {
+        PluginDescriptor {
+            abi_version,
+            name: c"test".as_ptr(),
+            parts: PARTS.as_ptr(),
+            parts_len: PARTS.len(),
+            solve: Some(solve),
+            free_string: Some(free_string),
+        }
+    }
+
+    #[test]
+    fn test_solve() {
+        let descriptor = descriptor(ABI_VERSION);
+        let plugin = unsafe { Plugin::from_descriptor(&descriptor, None) }.unwrap();
+
+        assert_eq!("test", plugin.name());
+        assert_eq!(&[(2022, 6, 1), (2022, 6, 2)], plugin.parts());
+        assert_eq!("5", plugin.solve(2022, 6, 1, "abcde").unwrap());
+
+        let err = plugin.solve(2022, 6, 2, "abcde").unwrap_err();
+        assert_eq!(
+            "test failed with error code 7: not solved yet",
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn test_wrong_abi_version() {
+        let descriptor = descriptor(ABI_VERSION + 1);
+
+        assert!(unsafe { Plugin::from_descriptor(&descriptor, None) }.is_err());
+        assert!(unsafe { Plugin::from_descriptor(ptr::null(), None) }.is_err());
+    }
+
+    #[test]
+    fn test_missing_functions() {
+        let mut no_solve = descriptor(ABI_VERSION);
+        no_solve.solve = None;
+        let err = unsafe { Plugin::from_descriptor(&no_solve, None) }
+            .err()
+            .unwrap();
+        assert_eq!(
+            "Plugin test is missing solve or free_string",
+            err.to_string()
+        );
+
+        let mut no_free = descriptor(ABI_VERSION);
+        no_free.free_string = None;
+        assert!(unsafe { Plugin::from_descriptor(&no_free, None) }.is_err());
+    }
+
+    #[test]
+    fn test_header_abi_version() {
+        let header = include_str!("../include/aoc_plugin.h");
+
+        assert!(header.contains(&format!("#define AOC_PLUGIN_ABI_VERSION {}\n", ABI_VERSION)));
+    }
+}
@@ -0,0 +1,163 @@
+//! Progress reporting for long loops.
+//!
+//! A solver creates a [`Progress`] with the total amount of work (if it knows
+//! it) and advances it as it goes. Whether anything is drawn is up to the
+//! harness: [`run_with`] turns the bar on for the current thread, and without
+//! it [`Progress`] does nothing. The bar is drawn on stderr, at most every
+//! [`REDRAW_EVERY`], and removed again when the [`Progress`] is dropped.
+
+use std::cell::Cell;
+use std::io::{self, Write};
+use std::time::{Duration, Instant};
+
+/// The bar is not redrawn more often than this
+pub const REDRAW_EVERY: Duration = Duration::from_millis(100);
+
+const BAR_WIDTH: usize = 30;
+
+thread_local! {
+    static ENABLED: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Runs `f` with progress bars drawn (or not) on this thread
+pub fn run_with<T>(enabled: bool, f: impl FnOnce() -> T) -> T {
+    let previous = ENABLED.with(|e| e.replace(enabled));
+    let result = f();
+    ENABLED.with(|e| e.set(previous));
+
+    result
+}
+
+pub struct Progress {
+    /// what is being counted, e.g. "rows"
+    unit: &'static str,
+    total: Option<u64>,
+    done: u64,
+
+    enabled: bool,
+    start: Instant,
+    last_draw: Option<Instant>,
+
+    /// the clock is only read every `stride` steps to keep tight loops fast
+    stride: u64,
+    next_check: u64,
+}
+
+impl Progress {
+    pub fn new(total: u64, unit: &'static str) -> Self {
+        Progress::create(Some(total), unit)
+    }
+
+    /// Progress for work whose size is not known up front
+    pub fn unbounded(unit: &'static str) -> Self {
+        Progress::create(None, unit)
+    }
+
+    fn create(total: Option<u64>, unit: &'static str) -> Self {
+        let stride = total.map_or(1, |t| (t / 1000).max(1));
+
+        Progress {
+            unit,
+            total,
+            done: 0,
+            enabled: ENABLED.with(Cell::get),
+            start: Instant::now(),
+            last_draw: None,
+            stride,
+            next_check: stride,
+        }
+    }
+
+    pub fn inc(&mut self) {
+        self.set(self.done + 1);
+    }
+
+    pub fn set(&mut self, done: u64) {
+        self.done = done;
+
+        if !self.enabled || self.done < self.next_check {
+            return;
+        }
+        self.next_check = self.done + self.stride;
+
+        let now = Instant::now();
+        if self
+            .last_draw
+            .is_some_and(|last| now.duration_since(last) < REDRAW_EVERY)
+        {
+            return;
+        }
+        self.last_draw = Some(now);
+
+        let line = render(self.done, self.total, self.unit, now - self.start);
+        let _ = write!(io::stderr(), "\r{}\x1b[K", line);
+    }
+}
+
+impl Drop for Progress {
+    fn drop(&mut self) {
+        if self.last_draw.is_some() {
+            let _ = write!(io::stderr(), "\r\x1b[K");
+        }
+    }
+}
+
+/// A single line such as `[#######.......] 812000/4000000 rows 20% ETA 3.2s`
+fn render(done: u64, total: Option<u64>, unit: &str, elapsed: Duration) -> String {
+    let Some(total) = total.filter(|t| *t > 0) else {
+        return format!("{} {} in {:.1?}", done, unit, elapsed);
+    };
+
+    let fraction = (done as f64 / total as f64).min(1.0);
+    let filled = (fraction * BAR_WIDTH as f64) as usize;
+
+    let eta = if done == 0 {
+        "?".to_string()
+    } else {
+        let remaining = elapsed.mul_f64((total.saturating_sub(done)) as f64 / done as f64);
+        format!("{:.1?}", remaining)
+    };
+
+    format!(
+        "[{}{}] {}/{} {} {:.0}% ETA {}",
+        "#".repeat(filled),
+        ".".repeat(BAR_WIDTH - filled),
+        done,
+        total,
+        unit,
+        fraction * 100.0,
+        eta
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let line = render(1, Some(4), "rows", Duration::from_secs(2));
+
+        assert_eq!(
+            "[#######.......................] 1/4 rows 25% ETA 6.0s",
+            line
+        );
+    }
+
+    #[test]
+    fn test_render_unbounded() {
+        let line = render(120, None, "grains", Duration::from_millis(1500));
+
+        assert_eq!("120 grains in 1.5s", line);
+    }
+
+    #[test]
+    fn test_disabled_by_default() {
+        let mut progress = Progress::new(10, "rounds");
+        progress.inc();
+
+        assert!(!progress.enabled);
+        assert_eq!(None, progress.last_draw);
+        assert!(run_with(true, || Progress::new(10, "rounds").enabled));
+    }
+}
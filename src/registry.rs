@@ -0,0 +1,192 @@
+//! Every solver the binary knows about, keyed by year, day and part.
+//!
+//! The built-in days are always registered under [`YEAR`]. Plugins add their parts next to
+//! them, so a day can have more than one solver and each answer is reported
+//! under the name of the solver that produced it. Every solver is handed the
+//! same input, read once by [`read_input`].
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+use anyhow::Result;
+
+use crate::plugin::Plugin;
+use crate::utils::read_file;
+use crate::*;
+
+/// The name answers from the modules in this crate are reported under
+pub const BUILT_IN: &str = "built-in";
+
+/// The `solve` function of a day module
+type SolveFn = fn(u8, &str) -> Result<String>;
+
+enum Solver {
+    BuiltIn(SolveFn),
+    Plugin(Rc<Plugin>),
+}
+
+pub struct Entry {
+    source: String,
+    version: String,
+    solver: Solver,
+}
+
+impl Entry {
+    /// Where the solver came from: [`BUILT_IN`] or the name of a plugin
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Changes whenever the solver could give a different answer
+    pub fn version(&self) -> &str {
+        &self.version
+    }
+
+    pub fn solve(&self, year: u16, day: u8, part: u8, input: &str) -> Result<String> {
+        match &self.solver {
+            Solver::BuiltIn(solve) => solve(part, input),
+            Solver::Plugin(plugin) => plugin.solve(year, day, part, input),
+        }
+    }
+}
+
+/// Where the puzzle inputs live unless another directory is configured
+pub const INPUT_DIR: &str = "./resources";
+
+/// Where the puzzle input for a day lives: `<dir>/<year>/day<day>.txt`.
+/// The 2022 inputs predate the per-year folders, so for [`YEAR`] the flat
+/// `<dir>/day<day>.txt` is used when there is no file in `<dir>/2022/`.
+pub fn input_path(dir: &Path, year: u16, day: u8) -> PathBuf {
+    let path = dir.join(year.to_string()).join(format!("day{}.txt", day));
+
+    if year == YEAR && !path.exists() {
+        return dir.join(format!("day{}.txt", day));
+    }
+
+    path
+}
+
+/// Reads the puzzle input for a day, see [`input_path`]
+pub fn read_input(dir: &Path, year: u16, day: u8) -> Result<String> {
+    let path = input_path(dir, year, day);
+    read_file(&path.to_string_lossy())
+}
+
+#[derive(Default)]
+pub struct Registry {
+    entries: BTreeMap<(u16, u8, u8), Vec<Entry>>,
+}
+
+impl Registry {
+    /// A registry with both parts of every day in this crate, under [`YEAR`]
+    pub fn with_built_ins() -> Self {
+        // Bump a day's version when a change could change its answers, so
+        // the answers cached by the old version are recomputed
+        let days: [(SolveFn, u32); 15] = [
+            (day01::solve, 3),
+            (day02::solve, 2),
+            (day03::solve, 3),
+            (day04::solve, 1),
+            (day05::solve, 2),
+            (day06::solve, 2),
+            (day07::solve, 2),
+            (day08::solve, 2),
+            (day09::solve, 1),
+            (day10::solve, 1),
+            (day11::solve, 1),
+            (day12::solve, 2),
+            (day13::solve, 1),
+            (day14::solve, 1),
+            (day15::solve, 1),
+        ];
+
+        let mut registry = Registry::default();
+        for (idx, (solve, version)) in days.into_iter().enumerate() {
+            for part in [1, 2] {
+                registry.add(
+                    (YEAR, idx as u8 + 1, part),
+                    BUILT_IN,
+                    &version.to_string(),
+                    Solver::BuiltIn(solve),
+                );
+            }
+        }
+
+        registry
+    }
+
+    pub fn register_plugin(&mut self, plugin: Plugin) {
+        let plugin = Rc::new(plugin);
+        for &key in plugin.parts() {
+            self.add(
+                key,
+                plugin.name(),
+                plugin.version(),
+                Solver::Plugin(Rc::clone(&plugin)),
+            );
+        }
+    }
+
+    fn add(&mut self, key: (u16, u8, u8), source: &str, version: &str, solver: Solver) {
+        self.entries.entry(key).or_default().push(Entry {
+            source: source.to_string(),
+            version: version.to_string(),
+            solver,
+        });
+    }
+
+    /// The solvers registered for a part, built-in first
+    pub fn solvers(&self, year: u16, day: u8, part: u8) -> &[Entry] {
+        self.entries
+            .get(&(year, day, part))
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Every part something is registered for, ordered by year, day and part
+    pub fn parts(&self) -> impl Iterator<Item = (u16, u8, u8)> + '_ {
+        self.entries.keys().copied()
+    }
+
+    /// The highest day anything is registered for in a year
+    pub fn last_day(&self, year: u16) -> Option<u8> {
+        self.entries
+            .keys()
+            .filter(|(y, _, _)| *y == year)
+            .map(|(_, day, _)| *day)
+            .max()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_built_ins() {
+        let registry = Registry::with_built_ins();
+
+        assert_eq!(Some(15), registry.last_day(2022));
+        assert_eq!(None, registry.last_day(2023));
+        assert_eq!(1, registry.solvers(2022, 1, 2).len());
+        assert_eq!(BUILT_IN, registry.solvers(2022, 15, 1)[0].source());
+        assert!(registry.solvers(2022, 1, 3).is_empty());
+        assert!(registry.solvers(2022, 16, 1).is_empty());
+        assert!(registry.solvers(2023, 1, 1).is_empty());
+        assert_eq!(30, registry.parts().count());
+        assert_eq!(Some((2022, 1, 1)), registry.parts().next());
+    }
+
+    #[test]
+    fn test_input_path() {
+        assert_eq!(
+            Path::new("./resources/day1.txt"),
+            input_path(Path::new(INPUT_DIR), 2022, 1).as_path()
+        );
+        assert_eq!(
+            Path::new("./resources/2023/day1.txt"),
+            input_path(Path::new(INPUT_DIR), 2023, 1).as_path()
+        );
+    }
+}
@@ -0,0 +1,269 @@
+//! The results table in README.md, rebuilt from actual runs by the `report`
+//! subcommand.
+//!
+//! The table lives between [`BEGIN`] and [`END`] so the rest of the README can
+//! be edited by hand. Everything between the markers is replaced on each run.
+
+use std::path::Path;
+use std::time::Duration;
+
+use anyhow::{bail, Result};
+
+use crate::harness::{Outcome, Run};
+use crate::YEAR;
+
+pub const BEGIN: &str = "<!-- results:begin -->";
+pub const END: &str = "<!-- results:end -->";
+
+const TITLES: [&str; 15] = [
+    "Calorie Counting",
+    "Rock Paper Scissors",
+    "Rucksack Reorganization",
+    "Camp Cleanup",
+    "Supply Stacks",
+    "Tuning Trouble",
+    "No Space Left On Device",
+    "Treetop Tree House",
+    "Rope Bridge",
+    "Cathode-Ray Tube",
+    "Monkey in the Middle",
+    "Hill Climbing Algorithm",
+    "Distress Signal",
+    "Regolith Reservoir",
+    "Beacon Exclusion Zone",
+];
+
+/// The puzzle's title, for the days in this crate
+pub fn title(year: u16, day: u8) -> Option<&'static str> {
+    if year != YEAR {
+        return None;
+    }
+    TITLES.get((day as usize).checked_sub(1)?).copied()
+}
+
+/// Where the module for a built-in day lives, relative to the project root:
+/// `src/dayNN.rs`, or `src/dayNN/mod.rs` for the days split into several files
+pub fn source_path(root: &Path, year: u16, day: u8) -> Option<String> {
+    if year != YEAR {
+        return None;
+    }
+
+    [
+        format!("src/day{:02}.rs", day),
+        format!("src/day{:02}/mod.rs", day),
+    ]
+    .into_iter()
+    .find(|path| root.join(path).exists())
+}
+
+/// One line of the table
+#[derive(Debug)]
+pub struct Row {
+    pub day: u8,
+    pub title: Option<&'static str>,
+    pub source: Option<String>,
+
+    /// The runs of every solver, for each part
+    pub parts: Vec<(u8, Vec<Run>)>,
+}
+
+impl Row {
+    /// A part counts as solved when every solver gave the same answer
+    fn solved(runs: &[Run]) -> bool {
+        let answers: Vec<&str> = runs.iter().filter_map(Run::answer).collect();
+        !answers.is_empty()
+            && answers.len() == runs.len()
+            && answers.iter().all(|a| *a == answers[0])
+    }
+
+    /// How long the first solver took, or why it did not finish
+    fn runtime(runs: &[Run]) -> String {
+        match runs.first() {
+            Some(Run {
+                outcome: Outcome::Solved { .. },
+                elapsed,
+                ..
+            }) => format_duration(*elapsed),
+            Some(Run {
+                outcome: Outcome::Cancelled(_),
+                ..
+            }) => "timed out".to_string(),
+            Some(Run {
+                outcome: Outcome::Failed(_),
+                ..
+            }) => "failed".to_string(),
+            None => "-".to_string(),
+        }
+    }
+}
+
+fn format_duration(elapsed: Duration) -> String {
+    format!("{:.2?}", elapsed)
+}
+
+/// The Markdown table for `rows`, with a column for each part
+pub fn render_table(rows: &[Row]) -> String {
+    let part_count = rows
+        .iter()
+        .flat_map(|row| row.parts.iter().map(|(part, _)| *part))
+        .max()
+        .unwrap_or(2);
+
+    let mut header = vec!["Day".to_string(), "Title".to_string(), "Solved".to_string()];
+    header.extend((1..=part_count).map(|part| format!("Part {}", part)));
+    header.push("Source".to_string());
+
+    let mut lines = vec![
+        format!("| {} |", header.join(" | ")),
+        format!("|{}", "---|".repeat(header.len())),
+    ];
+
+    for row in rows {
+        let solved = row
+            .parts
+            .iter()
+            .filter(|(_, runs)| Row::solved(runs))
+            .count();
+
+        let mut cells = vec![
+            row.day.to_string(),
+            row.title.unwrap_or("").to_string(),
+            format!("{}/{}", solved, row.parts.len()),
+        ];
+        cells.extend((1..=part_count).map(|part| {
+            row.parts
+                .iter()
+                .find(|(p, _)| *p == part)
+                .map_or("-".to_string(), |(_, runs)| Row::runtime(runs))
+        }));
+        cells.push(match &row.source {
+            Some(path) => format!("[{}]({})", path, path),
+            None => "-".to_string(),
+        });
+
+        lines.push(format!("| {} |", cells.join(" | ")));
+    }
+
+    lines.join("\n")
+}
+
+/// Replaces what is between the markers in `readme` with `table`
+pub fn replace_section(readme: &str, table: &str) -> Result<String> {
+    let (Some(begin), Some(end)) = (readme.find(BEGIN), readme.find(END)) else {
+        bail!("Could not find the {} and {} markers", BEGIN, END);
+    };
+    if end < begin {
+        bail!("{} comes before {}", END, BEGIN);
+    }
+
+    Ok(format!(
+        "{}{}\n\n{}\n\n{}",
+        &readme[..begin],
+        BEGIN,
+        table,
+        &readme[end..]
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cancel::Cancelled;
+    use std::fs;
+
+    fn run(outcome: Outcome, millis: u64) -> Run {
+        Run {
+            source: "built-in".to_string(),
+            outcome,
+            elapsed: Duration::from_millis(millis),
+            memory: None,
+        }
+    }
+
+    fn solved(answer: &str, millis: u64) -> Run {
+        run(
+            Outcome::Solved {
+                answer: answer.to_string(),
+                cached: false,
+            },
+            millis,
+        )
+    }
+
+    #[test]
+    fn test_render_table() {
+        let rows = vec![
+            Row {
+                day: 1,
+                title: title(2022, 1),
+                source: Some("src/day01.rs".to_string()),
+                parts: vec![(1, vec![solved("24000", 2)]), (2, vec![solved("45000", 3)])],
+            },
+            Row {
+                day: 15,
+                title: title(2022, 15),
+                source: Some("src/day15/mod.rs".to_string()),
+                parts: vec![
+                    (1, vec![solved("26", 1500)]),
+                    (
+                        2,
+                        vec![run(
+                            Outcome::Cancelled(Cancelled {
+                                timed_out: true,
+                                progress: "10 rows".to_string(),
+                            }),
+                            10000,
+                        )],
+                    ),
+                ],
+            },
+            Row {
+                day: 16,
+                title: None,
+                source: None,
+                parts: vec![(1, vec![solved("1651", 20), solved("1650", 30)])],
+            },
+        ];
+
+        assert_eq!(
+            "| Day | Title | Solved | Part 1 | Part 2 | Source |
+|---|---|---|---|---|---|
+| 1 | Calorie Counting | 2/2 | 2.00ms | 3.00ms | [src/day01.rs](src/day01.rs) |
+| 15 | Beacon Exclusion Zone | 1/2 | 1.50s | timed out | [src/day15/mod.rs](src/day15/mod.rs) |
+| 16 |  | 0/1 | 20.00ms | - | - |",
+            render_table(&rows)
+        );
+    }
+
+    #[test]
+    fn test_replace_section() {
+        let readme = format!("# AoC\n\n{}\nold table\n{}\n\nLessons\n", BEGIN, END);
+
+        assert_eq!(
+            format!("# AoC\n\n{}\n\nnew table\n\n{}\n\nLessons\n", BEGIN, END),
+            replace_section(&readme, "new table").unwrap()
+        );
+        assert!(replace_section("# AoC\n", "new table").is_err());
+    }
+
+    #[test]
+    fn test_source_path() {
+        let root = std::env::temp_dir().join(format!("aoc-report-test-{}", std::process::id()));
+        fs::create_dir_all(root.join("src/day13")).unwrap();
+        fs::write(root.join("src/day04.rs"), "").unwrap();
+        fs::write(root.join("src/day13/mod.rs"), "").unwrap();
+
+        assert_eq!(
+            Some("src/day04.rs".to_string()),
+            source_path(&root, 2022, 4)
+        );
+        assert_eq!(
+            Some("src/day13/mod.rs".to_string()),
+            source_path(&root, 2022, 13)
+        );
+        assert_eq!(None, source_path(&root, 2022, 16));
+        assert_eq!(None, source_path(&root, 2023, 4));
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,105 @@
+//! Golden file snapshots for the text renderings.
+//!
+//! Snapshots live in `src/snapshots/<name>.txt`. When a rendering changes the
+//! test fails with a line diff against the stored file. Run the tests with
+//! `UPDATE_SNAPSHOTS=1` to accept the new output and rewrite the files.
+
+use std::fs;
+use std::path::PathBuf;
+
+fn snapshot_path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("src")
+        .join("snapshots")
+        .join(format!("{}.txt", name))
+}
+
+fn update_mode() -> bool {
+    std::env::var("UPDATE_SNAPSHOTS").is_ok_and(|v| v == "1")
+}
+
+/// Compares `actual` against the golden file for `name`, or rewrites the
+/// golden file when running in update mode.
+#[track_caller]
+pub fn assert_snapshot(name: &str, actual: &str) {
+    let path = snapshot_path(name);
+
+    if update_mode() {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, actual)
+            .unwrap_or_else(|e| panic!("Could not write {}: {}", path.display(), e));
+        return;
+    }
+
+    let expected = fs::read_to_string(&path).unwrap_or_else(|_| {
+        panic!(
+            "Missing snapshot {}. Run the tests with UPDATE_SNAPSHOTS=1 to create it.",
+            path.display()
+        )
+    });
+
+    if expected != actual {
+        panic!(
+            "Snapshot {} does not match {}\n{}\nRun the tests with UPDATE_SNAPSHOTS=1 to accept the new output.",
+            name,
+            path.display(),
+            diff(&expected, actual)
+        );
+    }
+}
+
+/// A line diff based on the longest common subsequence. Lines only in the
+/// snapshot are prefixed with `-` and lines only in the new output with `+`.
+fn diff(expected: &str, actual: &str) -> String {
+    let old: Vec<&str> = expected.split('\n').collect();
+    let new: Vec<&str> = actual.split('\n').collect();
+
+    // lcs[i][j] is the length of the LCS of old[i..] and new[j..]
+    let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut out = String::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            out.push_str(&format!(" {}\n", old[i]));
+            i += 1;
+            j += 1;
+        } else if i < old.len() && (j == new.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            out.push_str(&format!("-{}\n", old[i]));
+            i += 1;
+        } else {
+            out.push_str(&format!("+{}\n", new[j]));
+            j += 1;
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff() {
+        let result = diff("a\nb\nc", "a\nx\nc");
+
+        assert_eq!(" a\n-b\n+x\n c\n", result);
+    }
+
+    #[test]
+    fn test_diff_added_lines() {
+        let result = diff("a", "a\nb");
+
+        assert_eq!(" a\n+b\n", result);
+    }
+}
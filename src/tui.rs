@@ -0,0 +1,350 @@
+//! An interactive terminal viewer for stepping through the simulations.
+//!
+//! Days with a simulation wrap their existing types in a [`Simulation`] and
+//! the viewer drives it one step at a time. The simulations print a lot as
+//! they go, so while the viewer is open stdout is sent to `/dev/null` and the
+//! viewer draws on stderr instead.
+//!
+//! Keys: `space` play/pause, `n` single step, `g` run to a step count,
+//! arrows or `hjkl` scroll, `+`/`-` change speed, `q` quit.
+
+use std::io::{self, Stderr, Write};
+use std::time::Duration;
+
+use anyhow::Result;
+use crossterm::{
+    cursor::{Hide, MoveTo, Show},
+    event::{self, Event, KeyCode, KeyEvent, KeyEventKind},
+    execute, queue,
+    style::Print,
+    terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen},
+};
+
+use crate::utils::SilencedStdout;
+
+/// A simulation that can be driven one step at a time
+pub trait Simulation {
+    /// Advances a single step. Returns false once there is nothing left to do.
+    fn step(&mut self) -> Result<bool>;
+
+    /// The current state, one entry per line
+    fn render(&self) -> Vec<String>;
+
+    /// A short summary of the current state for the status bar
+    fn status(&self) -> String;
+}
+
+/// Steps taken between redraws when running to a step count
+const BATCH: usize = 1000;
+
+struct Viewer {
+    step: usize,
+
+    playing: bool,
+    finished: bool,
+
+    /// step count to run to without pausing in between
+    target: Option<usize>,
+
+    /// digits typed after pressing `g`
+    input: Option<String>,
+
+    /// columns and lines scrolled off the top left of the viewport
+    scroll: (usize, usize),
+
+    delay: Duration,
+
+    quit: bool,
+}
+
+impl Viewer {
+    fn new() -> Self {
+        Viewer {
+            step: 0,
+            playing: false,
+            finished: false,
+            target: None,
+            input: None,
+            scroll: (0, 0),
+            delay: Duration::from_millis(100),
+            quit: false,
+        }
+    }
+
+    fn advance(&mut self, sim: &mut dyn Simulation) -> Result<()> {
+        if self.finished {
+            return Ok(());
+        }
+
+        if sim.step()? {
+            self.step += 1;
+        } else {
+            self.finished = true;
+            self.playing = false;
+            self.target = None;
+        }
+
+        if self.target.is_some_and(|t| self.step >= t) {
+            self.target = None;
+        }
+
+        Ok(())
+    }
+
+    fn handle(&mut self, key: KeyEvent, sim: &mut dyn Simulation) -> Result<()> {
+        if let Some(input) = &mut self.input {
+            match key.code {
+                KeyCode::Char(c) if c.is_ascii_digit() => input.push(c),
+                KeyCode::Backspace => {
+                    input.pop();
+                }
+                KeyCode::Enter => {
+                    self.target = input.parse().ok().filter(|t| *t > self.step);
+                    self.playing = false;
+                    self.input = None;
+                }
+                KeyCode::Esc => self.input = None,
+                _ => {}
+            }
+            return Ok(());
+        }
+
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => self.quit = true,
+            KeyCode::Char(' ') => self.playing = !self.playing && !self.finished,
+            KeyCode::Char('n') | KeyCode::Char('.') => {
+                self.playing = false;
+                self.target = None;
+                self.advance(sim)?;
+            }
+            KeyCode::Char('g') => self.input = Some(String::new()),
+            KeyCode::Char('+') | KeyCode::Char('=') => {
+                self.delay = (self.delay / 2).max(Duration::from_millis(1))
+            }
+            KeyCode::Char('-') => self.delay = (self.delay * 2).min(Duration::from_secs(2)),
+            KeyCode::Left | KeyCode::Char('h') => self.scroll.0 = self.scroll.0.saturating_sub(4),
+            KeyCode::Right | KeyCode::Char('l') => self.scroll.0 += 4,
+            KeyCode::Up | KeyCode::Char('k') => self.scroll.1 = self.scroll.1.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('j') => self.scroll.1 += 1,
+            KeyCode::Home | KeyCode::Char('0') => self.scroll = (0, 0),
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    fn state(&self) -> &'static str {
+        if self.finished {
+            "finished"
+        } else if self.target.is_some() {
+            "running"
+        } else if self.playing {
+            "playing"
+        } else {
+            "paused"
+        }
+    }
+
+    fn draw(&self, out: &mut Stderr, title: &str, sim: &dyn Simulation) -> Result<()> {
+        let (width, height) = terminal::size()?;
+        let (width, height) = (width as usize, height as usize);
+
+        queue!(out, Clear(ClearType::All), MoveTo(0, 0))?;
+
+        let header = format!(
+            "{} | step {} | {} | {}ms | {}",
+            title,
+            self.step,
+            self.state(),
+            self.delay.as_millis(),
+            sim.status()
+        );
+        queue!(out, Print(truncate(&header, 0, width)))?;
+
+        let lines = sim.render();
+        for (row, line) in lines
+            .iter()
+            .skip(self.scroll.1)
+            .take(height.saturating_sub(2))
+            .enumerate()
+        {
+            queue!(
+                out,
+                MoveTo(0, row as u16 + 1),
+                Print(truncate(line, self.scroll.0, width))
+            )?;
+        }
+
+        let footer = match &self.input {
+            Some(input) => format!("Run to step: {}", input),
+            None => "space play/pause | n step | g run to step | arrows scroll | +/- speed | q quit"
+                .to_string(),
+        };
+        queue!(
+            out,
+            MoveTo(0, height.saturating_sub(1) as u16),
+            Print(truncate(&footer, 0, width))
+        )?;
+
+        out.flush()?;
+        Ok(())
+    }
+}
+
+fn truncate(line: &str, skip: usize, width: usize) -> String {
+    line.chars().skip(skip).take(width).collect()
+}
+
+/// Opens the viewer on `sim` and runs until the user quits
+pub fn run(title: &str, sim: &mut dyn Simulation) -> Result<()> {
+    let _terminal = TerminalGuard::enter()?;
+    let mut out = io::stderr();
+
+    let mut viewer = Viewer::new();
+
+    while !viewer.quit {
+        viewer.draw(&mut out, title, sim)?;
+
+        if viewer.target.is_some() {
+            for _ in 0..BATCH {
+                viewer.advance(sim)?;
+                if viewer.target.is_none() {
+                    break;
+                }
+            }
+            if event::poll(Duration::ZERO)? {
+                handle_event(&mut viewer, sim)?;
+            }
+        } else if viewer.playing {
+            if event::poll(viewer.delay)? {
+                handle_event(&mut viewer, sim)?;
+            } else {
+                viewer.advance(sim)?;
+            }
+        } else {
+            handle_event(&mut viewer, sim)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn handle_event(viewer: &mut Viewer, sim: &mut dyn Simulation) -> Result<()> {
+    if let Event::Key(key) = event::read()? {
+        if key.kind == KeyEventKind::Press {
+            viewer.handle(key, sim)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Puts the terminal into raw mode on the alternate screen and silences
+/// stdout, putting everything back when dropped.
+struct TerminalGuard {
+    _stdout: SilencedStdout,
+}
+
+impl TerminalGuard {
+    fn enter() -> Result<Self> {
+        terminal::enable_raw_mode()?;
+        execute!(io::stderr(), EnterAlternateScreen, Hide)?;
+
+        Ok(TerminalGuard {
+            _stdout: SilencedStdout::new()?,
+        })
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        // stdout is restored after this, when the fields are dropped
+        let _ = execute!(io::stderr(), Show, LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::KeyModifiers;
+
+    /// Counts up to a limit
+    struct Counter {
+        count: usize,
+        limit: usize,
+    }
+
+    impl Simulation for Counter {
+        fn step(&mut self) -> Result<bool> {
+            if self.count == self.limit {
+                return Ok(false);
+            }
+            self.count += 1;
+            Ok(true)
+        }
+
+        fn render(&self) -> Vec<String> {
+            vec![self.count.to_string()]
+        }
+
+        fn status(&self) -> String {
+            String::new()
+        }
+    }
+
+    fn press(viewer: &mut Viewer, sim: &mut dyn Simulation, code: KeyCode) {
+        viewer
+            .handle(KeyEvent::new(code, KeyModifiers::NONE), sim)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_single_step() {
+        let mut sim = Counter { count: 0, limit: 2 };
+        let mut viewer = Viewer::new();
+
+        press(&mut viewer, &mut sim, KeyCode::Char('n'));
+        press(&mut viewer, &mut sim, KeyCode::Char('n'));
+        assert_eq!((2, false), (viewer.step, viewer.finished));
+
+        press(&mut viewer, &mut sim, KeyCode::Char('n'));
+        assert_eq!((2, true), (viewer.step, viewer.finished));
+
+        // can't start playing once finished
+        press(&mut viewer, &mut sim, KeyCode::Char(' '));
+        assert!(!viewer.playing);
+    }
+
+    #[test]
+    fn test_run_to_step() {
+        let mut sim = Counter { count: 0, limit: 100 };
+        let mut viewer = Viewer::new();
+
+        press(&mut viewer, &mut sim, KeyCode::Char('g'));
+        press(&mut viewer, &mut sim, KeyCode::Char('4'));
+        press(&mut viewer, &mut sim, KeyCode::Char('2'));
+        press(&mut viewer, &mut sim, KeyCode::Enter);
+        assert_eq!(Some(42), viewer.target);
+
+        while viewer.target.is_some() {
+            viewer.advance(&mut sim).unwrap();
+        }
+        assert_eq!(42, viewer.step);
+        assert_eq!(42, sim.count);
+    }
+
+    #[test]
+    fn test_scroll() {
+        let mut sim = Counter { count: 0, limit: 1 };
+        let mut viewer = Viewer::new();
+
+        press(&mut viewer, &mut sim, KeyCode::Up);
+        press(&mut viewer, &mut sim, KeyCode::Right);
+        press(&mut viewer, &mut sim, KeyCode::Down);
+        assert_eq!((4, 1), viewer.scroll);
+
+        press(&mut viewer, &mut sim, KeyCode::Home);
+        assert_eq!((0, 0), viewer.scroll);
+    }
+}
@@ -1,6 +1,6 @@
 use anyhow::{Context, Result};
 use std::fs::File;
-use std::io::Read;
+use std::io::{self, Read, Write};
 
 pub fn read_file(path: &str) -> Result<String> {
     let mut file = File::open(path).with_context(|| format!("Could not find {}", path))?;
@@ -10,10 +10,47 @@ pub fn read_file(path: &str) -> Result<String> {
     Ok(lines)
 }
 
-pub fn read_file_as_string_vec(path: &str) -> Result<Vec<String>> {
-    let lines = read_file(path)?;
+/// Sends stdout to `/dev/null` until dropped, for the solvers that print as
+/// they go. Does nothing on platforms other than unix.
+pub struct SilencedStdout {
+    #[cfg(unix)]
+    saved: libc::c_int,
+}
+
+impl SilencedStdout {
+    pub fn new() -> Result<Self> {
+        io::stdout().flush()?;
+
+        #[cfg(unix)]
+        // SAFETY: only file descriptors 1 and the ones opened here are touched
+        let saved = unsafe {
+            let saved = libc::dup(libc::STDOUT_FILENO);
+            let null = libc::open(c"/dev/null".as_ptr(), libc::O_WRONLY);
+            if null >= 0 {
+                libc::dup2(null, libc::STDOUT_FILENO);
+                libc::close(null);
+            }
+            saved
+        };
+
+        Ok(SilencedStdout {
+            #[cfg(unix)]
+            saved,
+        })
+    }
+}
 
-    let v: Vec<String> = lines.split('\n').map(|s| s.to_owned()).collect();
+impl Drop for SilencedStdout {
+    fn drop(&mut self) {
+        let _ = io::stdout().flush();
 
-    Ok(v)
+        #[cfg(unix)]
+        // SAFETY: restores the descriptor saved in new()
+        unsafe {
+            if self.saved >= 0 {
+                libc::dup2(self.saved, libc::STDOUT_FILENO);
+                libc::close(self.saved);
+            }
+        }
+    }
 }